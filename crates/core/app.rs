@@ -10,11 +10,14 @@
 mod actions;
 #[path = "app_input.rs"]
 mod input;
+#[path = "app_live.rs"]
+mod live;
 #[path = "app_render.rs"]
 mod render;
 #[path = "app_results.rs"]
 mod results;
 
+use self::live::LiveOutput;
 use self::results::get_near_index;
 #[cfg(test)]
 use self::results::{command_results_equivalent, gen_diff_only_data, gen_result_items};
@@ -29,24 +32,27 @@ use std::sync::{Arc, Mutex};
 use std::{
     collections::HashMap,
     io::{self, Write},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tui::{backend::Backend, style::Color, Terminal};
 
 // local module
-use crate::common::OutputMode;
+use crate::baseline::Baseline;
+use crate::common::{exit_code, ExitOnMatch, ExitOnStatus, OutputMode};
 use crate::event::AppEvent;
 use crate::exec::CommandResult;
-use crate::header::HeaderArea;
+use crate::header::{HeaderArea, LogSource};
 use crate::help::HelpWindow;
-use crate::history::{HistoryArea, HistorySummary};
+use crate::history::{HistoryArea, HistoryColumn, HistorySummary};
 use crate::hwatch_ansi::get_ansi_strip_str;
 use crate::hwatch_diffmode::DiffMode;
 use crate::keymap::{default_keymap, Keymap};
 use crate::output;
+use crate::stable::StableFilter;
 use crate::watch::WatchArea;
 // local const
 use crate::SharedInterval;
+use crate::SharedPtySize;
 use crate::DEFAULT_TAB_SIZE;
 
 ///
@@ -64,6 +70,7 @@ pub enum ActiveWindow {
     Exit,
     Delete,
     Clear,
+    Finished,
 }
 
 ///
@@ -84,6 +91,15 @@ pub struct ResultItems {
     // Strcut elements to with keyword filter.
     // ResultItems are created for each Output Type, so only one is generated in Sturct.
     pub diff_only_data: Vec<u8>,
+
+    /// changes dropped by `--stable-for` while this was the latest result.
+    pub flaps: u32,
+
+    /// runs with this output, counting the unchanged runs after it.
+    pub runs: u32,
+
+    /// timestamp of the last of those runs.
+    pub last_seen: String,
 }
 
 impl ResultItems {
@@ -94,6 +110,9 @@ impl ResultItems {
             summary: HistorySummary::init(),
 
             diff_only_data: vec![],
+            flaps: 0,
+            runs: 1,
+            last_seen: String::new(),
         }
     }
 
@@ -150,6 +169,31 @@ pub struct App<'a> {
     ///
     exit_on_change_armed: bool,
 
+    ///
+    exit_on_status: Option<ExitOnStatus>,
+
+    ///
+    exit_on_match: Option<ExitOnMatch>,
+
+    /// holds back changes until they last for `--stable-for` runs.
+    stable: Option<StableFilter>,
+
+    /// compare runs with this instead of the previous run.
+    baseline: Option<Baseline>,
+
+    /// history entry pinned as the baseline with a key.
+    pinned_baseline: Option<usize>,
+
+    /// pause and show a popup instead of exiting on `exit_on_status` or
+    /// `exit_on_match`.
+    pause_on_exit: bool,
+
+    /// why the watch stopped, shown in the finished popup.
+    finished_reason: String,
+
+    /// when the finished popup started flashing.
+    flash_started: Option<Instant>,
+
     ///
     is_border: bool,
 
@@ -215,6 +259,24 @@ pub struct App<'a> {
     ///
     interval: SharedInterval,
 
+    /// exit code of the most recent run, changed or not.
+    last_exit_code: i32,
+
+    /// output of the command while it is still running.
+    live: LiveOutput,
+
+    /// pty size of the command, kept in sync with the watch pane.
+    pty_size: Option<SharedPtySize>,
+
+    /// wakes the command thread for an immediate run.
+    run_now: Option<Sender<()>>,
+
+    /// results come from a logfile, and no command is running.
+    is_read_only: bool,
+
+    /// history entry the watch pane diffs against, instead of the previous one.
+    diff_base: Option<usize>,
+
     ///
     tab_size: u16,
 
@@ -243,6 +305,9 @@ pub struct App<'a> {
     /// logfile path.
     logfile: String,
 
+    /// also log unchanged runs, without their output.
+    log_heartbeat: bool,
+
     ///
     pub tx: Sender<AppEvent>,
 
@@ -286,6 +351,14 @@ impl App<'_> {
             is_beep: false,
             exit_on_change: None,
             exit_on_change_armed: false,
+            exit_on_status: None,
+            exit_on_match: None,
+            stable: None,
+            baseline: None,
+            pinned_baseline: None,
+            pause_on_exit: false,
+            finished_reason: String::new(),
+            flash_started: None,
             is_border: false,
             is_history_summary: false,
             summary_enabled: true,
@@ -308,6 +381,12 @@ impl App<'_> {
             enable_summary_char: false,
 
             interval: interval.clone(),
+            last_exit_code: 0,
+            live: LiveOutput::new(),
+            pty_size: None,
+            run_now: None,
+            is_read_only: false,
+            diff_base: None,
             tab_size: DEFAULT_TAB_SIZE,
 
             header_area: {
@@ -326,6 +405,7 @@ impl App<'_> {
 
             done: false,
             logfile: "".to_string(),
+            log_heartbeat: false,
             tx,
             rx,
         }
@@ -376,7 +456,11 @@ impl App<'_> {
 
             // get event
             match self.rx.recv_timeout(Duration::from_millis(100)) {
-                Ok(AppEvent::Redraw) => update_draw = true,
+                Ok(AppEvent::Redraw) => {
+                    // The command thread may have moved the next run time.
+                    self.header_area.update();
+                    update_draw = true;
+                }
 
                 // Get terminal event.
                 Ok(AppEvent::TerminalEvent(terminal_event)) => {
@@ -386,13 +470,42 @@ impl App<'_> {
 
                 // Get command result.
                 Ok(AppEvent::OutputUpdate(exec_result)) => {
-                    let changed = self.create_result_items(exec_result, true);
+                    self.live_output_committed();
+                    self.last_exit_code = exit_code(&exec_result);
+                    let status = exec_result.status;
+                    let matched = self.exit_on_match.as_ref().is_some_and(|exit_on_match| {
+                        exit_on_match.matches(
+                            &exec_result,
+                            self.output_mode,
+                            self.ignore_spaceblock,
+                        )
+                    });
+                    let changed = self.create_result_items(*exec_result, true);
 
                     if changed && self.is_beep {
                         println!("\x07")
                     }
 
                     self.handle_exit_on_change(changed);
+                    self.handle_exit_on_status(status);
+                    self.handle_exit_on_match(matched);
+                    update_draw = true;
+                }
+
+                // Get the output of a command that is still running.
+                Ok(AppEvent::RunStarted(seq)) => {
+                    self.live_run_started(seq);
+                    update_draw = true;
+                }
+                Ok(AppEvent::OutputChunk(seq, mode, chunk)) => {
+                    self.live_output_chunk(seq, mode, chunk)
+                }
+                Ok(AppEvent::RunRetrying(seq, attempt, attempts)) => {
+                    self.live_run_retrying(seq, attempt, attempts);
+                    update_draw = true;
+                }
+                Ok(AppEvent::RunFinished(seq)) => {
+                    self.live_run_finished(seq);
                     update_draw = true;
                 }
 
@@ -411,6 +524,10 @@ impl App<'_> {
                 Err(_) => {}
             }
 
+            if self.refresh_live_output() || self.is_flashing() {
+                update_draw = true;
+            }
+
             if update_draw {
                 self.watch_area.update_wrap();
             }
@@ -448,6 +565,13 @@ impl App<'_> {
         // set output mode
         self.printer.set_output_mode(mode);
 
+        // each output mode keeps its own history, so the base does not carry over
+        self.diff_base = None;
+        self.history_area.set_diff_base(None);
+
+        // the baseline is compared with the text of the new mode
+        self.update_baseline_status();
+
         // set output data
         if !self.results.is_empty() {
             // Switch the result depending on the output mode.
@@ -486,6 +610,33 @@ impl App<'_> {
         self.exit_on_change_armed = false;
     }
 
+    ///
+    pub fn set_exit_on_status(&mut self, exit_on_status: Option<ExitOnStatus>) {
+        self.exit_on_status = exit_on_status;
+    }
+
+    ///
+    pub fn set_exit_on_match(&mut self, exit_on_match: Option<ExitOnMatch>) {
+        self.exit_on_match = exit_on_match;
+    }
+
+    ///
+    pub fn set_stable_for(&mut self, runs: Option<u32>) {
+        self.stable = runs.map(StableFilter::new);
+    }
+
+    ///
+    pub fn set_baseline(&mut self, baseline: Option<Baseline>) {
+        self.baseline = baseline;
+        self.pinned_baseline = None;
+        self.update_baseline_status();
+    }
+
+    ///
+    pub fn set_pause_on_exit(&mut self, pause_on_exit: bool) {
+        self.pause_on_exit = pause_on_exit;
+    }
+
     ///
     pub fn set_border(&mut self, border: bool) {
         self.is_border = border;
@@ -575,6 +726,34 @@ impl App<'_> {
         self.enable_summary_char = enable_summary_char;
     }
 
+    ///
+    pub fn set_pty_size(&mut self, pty_size: SharedPtySize) {
+        self.pty_size = Some(pty_size);
+    }
+
+    ///
+    pub fn set_run_now(&mut self, run_now: Sender<()>) {
+        self.run_now = Some(run_now);
+    }
+
+    /// Show the results of a logfile without running anything. The keys that
+    /// change the interval, pause or run the command do nothing.
+    pub fn set_log_source(&mut self, log_source: LogSource) {
+        self.is_read_only = true;
+        self.header_area.set_log_source(Some(log_source));
+        self.header_area.update();
+    }
+
+    /// Exit code of the last run, for `--count` and `--duration`.
+    pub fn get_exit_code(&self) -> i32 {
+        self.last_exit_code
+    }
+
+    ///
+    pub fn set_history_columns(&mut self, history_columns: Vec<HistoryColumn>) {
+        self.history_area.set_columns(history_columns);
+    }
+
     ///
     pub fn set_mouse_events(&mut self, mouse_events: bool) {
         self.mouse_events = mouse_events;
@@ -597,22 +776,38 @@ impl App<'_> {
 
     ///
     fn increase_interval(&mut self) {
+        if self.is_read_only {
+            return;
+        }
         self.interval.write().unwrap().increase(0.5);
         self.header_area.update();
     }
 
     ///
     fn decrease_interval(&mut self) {
+        if self.is_read_only {
+            return;
+        }
         self.interval.write().unwrap().decrease(0.5);
         self.header_area.update();
     }
 
     ///
     fn toggle_pause(&mut self) {
+        if self.is_read_only {
+            return;
+        }
         self.interval.write().unwrap().toggle_pause();
         self.header_area.update();
     }
 
+    ///
+    fn run_now(&mut self) {
+        if let Some(run_now) = &self.run_now {
+            let _ = run_now.send(());
+        }
+    }
+
     ///
     pub fn set_diff_mode(&mut self, diff_mode: usize) {
         self.diff_mode = diff_mode;
@@ -670,6 +865,11 @@ impl App<'_> {
         self.logfile = logpath;
     }
 
+    ///
+    pub fn set_log_heartbeat(&mut self, log_heartbeat: bool) {
+        self.log_heartbeat = log_heartbeat;
+    }
+
     ///
     fn set_input_mode(&mut self, input_mode: InputMode) {
         self.input_mode = input_mode;
@@ -702,6 +902,7 @@ mod tests {
         layout::{Position, Size},
     };
 
+    use crate::diffmode_line::DiffModeAtLineDiff;
     use crate::diffmode_plane::DiffModeAtPlane;
     use crate::RunInterval;
 
@@ -847,6 +1048,216 @@ mod tests {
         assert!(result.is_ok(), "unexpected run() error: {result:?}");
     }
 
+    #[test]
+    fn until_failure_exits_on_a_failed_run() {
+        let (tx, rx) = unbounded();
+        let interval = Arc::new(RwLock::new(RunInterval::default()));
+        let mut app = App::new(tx, rx, interval, test_diff_modes(), 0);
+        app.set_exit_on_status(Some(ExitOnStatus::Failure));
+
+        app.handle_exit_on_status(true);
+        assert!(!app.done);
+        app.handle_exit_on_status(false);
+        assert!(app.done);
+    }
+
+    #[test]
+    fn until_pause_shows_a_popup_instead_of_exiting() {
+        let (tx, rx) = unbounded();
+        let interval = Arc::new(RwLock::new(RunInterval::default()));
+        let mut app = App::new(tx, rx, interval.clone(), test_diff_modes(), 0);
+        app.set_exit_on_status(Some(ExitOnStatus::Success));
+        app.set_pause_on_exit(true);
+
+        app.handle_exit_on_status(true);
+        assert!(!app.done);
+        assert!(app.window == ActiveWindow::Finished);
+        assert!(interval.read().unwrap().paused);
+
+        app.get_normal_input_key(Event::Key(KeyEvent::new(
+            KeyCode::Char('y'),
+            KeyModifiers::NONE,
+        )));
+        assert!(matches!(app.rx.try_recv(), Ok(AppEvent::Exit)));
+    }
+
+    #[test]
+    fn until_match_pause_shows_the_pattern_and_flashes() {
+        let (tx, rx) = unbounded();
+        let interval = Arc::new(RwLock::new(RunInterval::default()));
+        let mut app = App::new(tx, rx, interval, test_diff_modes(), 0);
+        app.set_exit_on_match(Some(ExitOnMatch::new(
+            regex::Regex::new("Running").unwrap(),
+            true,
+        )));
+        app.set_pause_on_exit(true);
+
+        app.handle_exit_on_match(false);
+        assert!(app.window == ActiveWindow::Normal);
+
+        app.handle_exit_on_match(true);
+        assert!(!app.done);
+        assert!(app.window == ActiveWindow::Finished);
+        assert_eq!(app.finished_reason, "The output matches 'Running'.");
+        assert!(app.is_flashing());
+    }
+
+    #[test]
+    fn replay_keeps_the_schedule_untouched() {
+        let (tx, rx) = unbounded();
+        let interval = Arc::new(RwLock::new(RunInterval::default()));
+        let mut app = App::new(tx, rx, interval.clone(), test_diff_modes(), 0);
+        app.set_log_source(LogSource::Replay("run.log".to_string()));
+
+        let before = interval.read().unwrap().interval;
+        app.toggle_pause();
+        app.increase_interval();
+        assert!(!interval.read().unwrap().paused);
+        assert_eq!(interval.read().unwrap().interval, before);
+    }
+
+    #[test]
+    fn diff_base_replaces_the_previous_history_until_cleared() {
+        let (tx, rx) = unbounded();
+        let interval = Arc::new(RwLock::new(RunInterval::default()));
+        let diff_modes: Vec<Arc<Mutex<Box<dyn DiffMode>>>> =
+            vec![Arc::new(Mutex::new(Box::new(DiffModeAtLineDiff::new())))];
+        let mut app = App::new(tx, rx, interval, diff_modes, 0);
+
+        let run = |output: &str| CommandResult::default().set_output(output.as_bytes().to_vec());
+        let watch_text = |app: &App| {
+            app.watch_area
+                .data
+                .iter()
+                .map(|line| line.to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        app.create_result_items(run("base"), true);
+        app.history_area.set_state_select(1);
+        app.action_set_diff_base();
+        assert_eq!(app.diff_base, Some(1));
+
+        app.create_result_items(run("middle"), true);
+        app.create_result_items(run("latest"), true);
+        app.history_area.set_state_select(0);
+        app.refresh_selected_watch_output();
+        assert!(watch_text(&app).contains("base"));
+        assert!(!watch_text(&app).contains("middle"));
+
+        app.action_clear_diff_base();
+        assert!(!watch_text(&app).contains("base"));
+        assert!(watch_text(&app).contains("middle"));
+
+        app.action_set_diff_base();
+        assert_eq!(app.diff_base, Some(3));
+        app.set_output_mode(OutputMode::Stdout);
+        assert_eq!(app.diff_base, None);
+    }
+
+    #[test]
+    fn pinned_baseline_makes_only_drift_from_it_a_change() {
+        let (tx, rx) = unbounded();
+        let interval = Arc::new(RwLock::new(RunInterval::default()));
+        let mut app = App::new(tx, rx, interval, test_diff_modes(), 0);
+
+        let run = |output: &str| CommandResult::default().set_output(output.as_bytes().to_vec());
+
+        app.create_result_items(run("golden"), true);
+        assert!(app.create_result_items(run("drift"), true));
+
+        app.history_area.set_state_select(1);
+        app.action_pin_baseline();
+        assert!(!app.create_result_items(run("golden"), true));
+        assert!(app.create_result_items(run("drift"), true));
+        // Repeating a drifted run keeps signalling it without a new entry.
+        assert!(app.create_result_items(run("drift"), true));
+        assert_eq!(app.results.len(), 5);
+
+        // Pinning the same entry again goes back to comparing with the previous run.
+        app.history_area.set_state_select(1);
+        app.action_pin_baseline();
+        assert!(app.baseline.is_none());
+        assert!(app.create_result_items(run("golden"), true));
+    }
+
+    #[test]
+    fn stable_for_counts_flaps_on_the_latest_result() {
+        let (tx, rx) = unbounded();
+        let interval = Arc::new(RwLock::new(RunInterval::default()));
+        let mut app = App::new(tx, rx, interval, test_diff_modes(), 0);
+        app.set_stable_for(Some(2));
+
+        let run = |output: &str| CommandResult::default().set_output(output.as_bytes().to_vec());
+
+        assert!(app.create_result_items(run("a"), true));
+        assert!(!app.create_result_items(run("b"), true));
+        assert!(!app.create_result_items(run("a"), true));
+        assert!(!app.create_result_items(run("b"), true));
+        assert!(!app.create_result_items(run("c"), true));
+        assert_eq!(app.results[&1].flaps, 2);
+
+        assert!(app.create_result_items(run("c"), true));
+        assert_eq!(app.results[&2].command_result.get_output(), "c");
+        assert_eq!(app.results[&2].flaps, 0);
+    }
+
+    #[test]
+    fn unchanged_runs_count_on_the_latest_result() {
+        let (tx, rx) = unbounded();
+        let interval = Arc::new(RwLock::new(RunInterval::default()));
+        let mut app = App::new(tx, rx, interval, test_diff_modes(), 0);
+
+        let run = |output: &str, timestamp: &str| CommandResult {
+            timestamp: timestamp.to_string(),
+            ..CommandResult::default().set_output(output.as_bytes().to_vec())
+        };
+
+        app.create_result_items(run("a", "2026-04-08 12:00:00.000"), true);
+        app.create_result_items(run("a", "2026-04-08 12:00:02.000"), true);
+        app.create_result_items(run("a", "2026-04-08 12:00:04.000"), true);
+        assert_eq!(app.results[&1].runs, 3);
+        assert_eq!(app.results[&1].last_seen, "2026-04-08 12:00:04.000");
+
+        // Heartbeats loaded from a logfile count the same way.
+        let heartbeat = run("", "2026-04-08 12:00:06.000").to_heartbeat();
+        app.add_results(vec![heartbeat]);
+        assert_eq!(app.results[&1].runs, 4);
+        assert_eq!(app.results.len(), 2);
+
+        app.create_result_items(run("b", "2026-04-08 12:00:08.000"), true);
+        assert_eq!(app.results[&2].runs, 1);
+    }
+
+    #[test]
+    fn stdin_change_is_reported_once_per_edit() {
+        let (tx, rx) = unbounded();
+        let interval = Arc::new(RwLock::new(RunInterval::default()));
+        let mut app = App::new(tx, rx, interval, test_diff_modes(), 0);
+
+        let run = |output: &str, stdin_hash: &str| CommandResult {
+            stdin_hash: stdin_hash.to_string(),
+            ..CommandResult::default().set_output(output.as_bytes().to_vec())
+        };
+
+        app.create_result_items(run("a", "11111111"), true);
+        app.create_result_items(run("b", "11111111"), true);
+        app.create_result_items(run("c", "22222222"), true);
+
+        let changes: Vec<Option<String>> = (1..=3)
+            .map(|index| results::get_stdin_change(&app.results, index))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                Some("11111111".to_string()),
+                None,
+                Some("22222222".to_string())
+            ]
+        );
+    }
+
     #[test]
     fn invalid_regex_filter_input_does_not_enable_filtering() {
         let (tx, rx) = unbounded();
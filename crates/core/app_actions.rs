@@ -2,9 +2,12 @@
 // Use of this source code is governed by an MIT license
 // that can be found in the LICENSE file.
 
+use super::results::{get_near_index, get_results_latest_index, get_stdin_change};
 use super::{ActiveArea, ActiveWindow, App, InputMode};
+use crate::baseline::Baseline;
 use crate::common::OutputMode;
 use crate::event::AppEvent;
+use crate::history::History;
 use regex::Regex;
 use tui::layout::Rect;
 
@@ -86,17 +89,16 @@ impl App<'_> {
             OutputMode::Stderr => &self.results_stderr,
         };
 
-        let timestamp = &results[&result_index].command_result.timestamp;
-        let status = &results[&result_index].command_result.status;
-
-        let history_summary = results[&result_index].summary.clone();
-
-        self.history_area.update(
-            timestamp.to_string(),
-            *status,
+        let mut history = History::from_result(
             result_index as u16,
-            history_summary,
+            &results[&result_index].command_result,
+            results[&result_index].summary.clone(),
         );
+        history.flaps = results[&result_index].flaps;
+        history.runs = results[&result_index].runs;
+        history.last_seen = results[&result_index].last_seen.clone();
+        history.stdin_change = get_stdin_change(results, result_index);
+        self.history_area.update(history);
 
         if selected != 0 {
             self.history_area.previous(1);
@@ -348,6 +350,64 @@ impl App<'_> {
         }
     }
 
+    /// Number of the selected history entry, with "latest" resolved.
+    fn selected_result_index(&self) -> Option<usize> {
+        let results = match self.output_mode {
+            OutputMode::Output => &self.results,
+            OutputMode::Stdout => &self.results_stdout,
+            OutputMode::Stderr => &self.results_stderr,
+        };
+        let selected = match self.history_area.get_state_select() {
+            0 => get_results_latest_index(results),
+            selected => get_near_index(results, selected),
+        };
+
+        match selected {
+            0 => None,
+            selected => Some(selected),
+        }
+    }
+
+    pub(super) fn action_set_diff_base(&mut self) {
+        let Some(selected) = self.selected_result_index() else {
+            return;
+        };
+        self.diff_base = Some(selected);
+        self.history_area.set_diff_base(self.diff_base);
+        self.refresh_selected_watch_output();
+    }
+
+    pub(super) fn action_clear_diff_base(&mut self) {
+        self.diff_base = None;
+        self.history_area.set_diff_base(None);
+        self.refresh_selected_watch_output();
+    }
+
+    /// Pin the selected history as the baseline, or unpin it when it
+    /// already is.
+    pub(super) fn action_pin_baseline(&mut self) {
+        let Some(selected) = self.selected_result_index() else {
+            return;
+        };
+
+        if self.pinned_baseline == Some(selected) {
+            self.baseline = None;
+            self.pinned_baseline = None;
+        } else {
+            let results = match self.output_mode {
+                OutputMode::Output => &self.results,
+                OutputMode::Stdout => &self.results_stdout,
+                OutputMode::Stderr => &self.results_stderr,
+            };
+            let result = results[&selected].command_result.clone();
+            self.baseline = Some(Baseline::new(Some(result)));
+            self.pinned_baseline = Some(selected);
+        }
+
+        self.update_baseline_status();
+        self.refresh_selected_watch_output();
+    }
+
     pub(super) fn action_next_keyword(&mut self) {
         self.watch_area.next_keyword();
     }
@@ -37,7 +37,7 @@ impl App<'_> {
     }
 
     pub(super) fn get_normal_input_key(&mut self, terminal_event: crossterm::event::Event) {
-        if self.window == ActiveWindow::Exit {
+        if matches!(self.window, ActiveWindow::Exit | ActiveWindow::Finished) {
             if let Event::Key(key) = terminal_event {
                 if key.kind == KeyEventKind::Press {
                     match key.code {
@@ -178,6 +178,10 @@ impl App<'_> {
                     InputAction::IntervalPlus => self.increase_interval(),
                     InputAction::IntervalMinus => self.decrease_interval(),
                     InputAction::TogglePause => self.toggle_pause(),
+                    InputAction::RunNow => self.run_now(),
+                    InputAction::SetDiffBase => self.action_set_diff_base(),
+                    InputAction::ClearDiffBase => self.action_clear_diff_base(),
+                    InputAction::PinBaseline => self.action_pin_baseline(),
                     InputAction::ChangeFilterMode => self.set_input_mode(InputMode::Filter),
                     InputAction::ChangeRegexFilterMode => {
                         self.set_input_mode(InputMode::RegexFilter)
@@ -237,7 +241,7 @@ impl App<'_> {
                     }
                     _ => {}
                 },
-                ActiveWindow::Exit => match action {
+                ActiveWindow::Exit | ActiveWindow::Finished => match action {
                     InputAction::Quit => self.exit(),
                     InputAction::Cancel => self.exit(),
                     InputAction::Reset => self.window = ActiveWindow::Normal,
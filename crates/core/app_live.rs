@@ -0,0 +1,141 @@
+// Copyright (c) 2026 Blacknon. All rights reserved.
+// Use of this source code is governed by an MIT license
+// that can be found in the LICENSE file.
+
+use super::App;
+use crate::common::OutputMode;
+use crate::exec::CommandResult;
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+// Limit how often the watch pane is re-rendered while output is streaming in.
+const LIVE_REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Output of the run that is still going, shown on the `latest` row.
+pub(super) struct LiveOutput {
+    /// runs that have started and not finished yet
+    running: BTreeSet<u64>,
+
+    /// run whose output is shown (the most recently started one)
+    seq: Option<u64>,
+
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+
+    /// chunks arrived since the last render
+    dirty: bool,
+    rendered_at: Instant,
+
+    /// the watch pane holds partial output instead of a recorded result
+    shown: bool,
+}
+
+impl LiveOutput {
+    pub(super) fn new() -> Self {
+        Self {
+            running: BTreeSet::new(),
+            seq: None,
+            stdout: vec![],
+            stderr: vec![],
+            dirty: false,
+            rendered_at: Instant::now(),
+            shown: false,
+        }
+    }
+}
+
+impl App<'_> {
+    pub(super) fn live_run_started(&mut self, seq: u64) {
+        self.live.running.insert(seq);
+        self.live.seq = Some(seq);
+        self.live.stdout.clear();
+        self.live.stderr.clear();
+        self.live.dirty = false;
+
+        self.header_area.set_running(true);
+        self.header_area.update();
+    }
+
+    /// The output of the failed attempt is dropped, and the header shows the
+    /// retry until the run finishes.
+    pub(super) fn live_run_retrying(&mut self, seq: u64, attempt: u32, attempts: u32) {
+        if self.live.seq == Some(seq) {
+            self.live.stdout.clear();
+            self.live.stderr.clear();
+            self.live.dirty = true;
+        }
+
+        self.header_area.set_retry(Some((attempt, attempts)));
+        self.header_area.update();
+    }
+
+    pub(super) fn live_output_chunk(&mut self, seq: u64, mode: OutputMode, chunk: Vec<u8>) {
+        if self.live.seq != Some(seq) {
+            return;
+        }
+
+        match mode {
+            OutputMode::Stderr => self.live.stderr.extend_from_slice(&chunk),
+            _ => self.live.stdout.extend_from_slice(&chunk),
+        }
+        self.live.dirty = true;
+    }
+
+    pub(super) fn live_run_finished(&mut self, seq: u64) {
+        self.live.running.remove(&seq);
+        if self.live.seq == Some(seq) {
+            // The result has already been shown through OutputUpdate.
+            self.live.seq = None;
+            self.live.stdout.clear();
+            self.live.stderr.clear();
+            self.live.dirty = false;
+        }
+
+        // An unchanged result is not redrawn by OutputUpdate, so put the
+        // recorded output back in place of the partial one.
+        if self.live.shown && self.live.seq.is_none() {
+            self.live.shown = false;
+            self.refresh_selected_watch_output();
+        }
+
+        if self.live.running.is_empty() {
+            self.header_area.set_running(false);
+            self.header_area.set_retry(None);
+            self.header_area.update();
+        }
+    }
+
+    /// A committed result replaces whatever partial output was on screen.
+    pub(super) fn live_output_committed(&mut self) {
+        self.live.dirty = false;
+    }
+
+    /// Render the streamed output if the `latest` row is selected. Returns true
+    /// when the watch pane was updated.
+    pub(super) fn refresh_live_output(&mut self) -> bool {
+        if !self.live.dirty || self.live.rendered_at.elapsed() < LIVE_REFRESH_INTERVAL {
+            return false;
+        }
+        if self.history_area.get_state_select() != 0 {
+            return false;
+        }
+
+        let mut output = self.live.stdout.clone();
+        output.extend_from_slice(&self.live.stderr);
+
+        let partial = CommandResult::default()
+            .set_output(output)
+            .set_stdout(self.live.stdout.clone())
+            .set_stderr(self.live.stderr.clone());
+
+        // Compare the partial output against itself, so nothing is highlighted
+        // as a change until the run is complete.
+        let render_data = self.printer.get_watch_data(&partial, &partial);
+        self.apply_watch_render_data(render_data);
+
+        self.live.dirty = false;
+        self.live.rendered_at = Instant::now();
+        self.live.shown = true;
+        true
+    }
+}
@@ -3,8 +3,8 @@
 // that can be found in the LICENSE file.
 
 use super::{ActiveArea, ActiveWindow, App, InputMode};
+use crate::exec::PtySize;
 use crate::popup::PopupWindow;
-use crate::HISTORY_WIDTH;
 use tui::{
     layout::{Constraint, Direction, Layout, Position, Rect},
     Frame,
@@ -69,6 +69,18 @@ impl App<'_> {
                     ],
                 );
             }
+            ActiveWindow::Finished => {
+                let mut popup_window = PopupWindow::new(
+                    "finished",
+                    vec![
+                        format!(" {}", self.finished_reason),
+                        "   Press 'Y' or 'Q'  : Quit.".to_string(),
+                        "   Press 'N' or 'Esc': Stay (paused).".to_string(),
+                    ],
+                )
+                .set_reversed(self.is_flash_on());
+                popup_window.draw(f);
+            }
         }
     }
 
@@ -100,7 +112,7 @@ impl App<'_> {
 
     fn define_subareas(&mut self, total_area: Rect) {
         let history_width: u16 = match self.show_history {
-            true => HISTORY_WIDTH,
+            true => self.history_area.get_width(),
             false => match self.area == ActiveArea::History
                 || self.history_area.get_state_select() != 0
             {
@@ -138,5 +150,24 @@ impl App<'_> {
 
         self.watch_area.set_area(main_chunks[0]);
         self.history_area.set_area(main_chunks[1]);
+
+        self.update_pty_size();
+    }
+
+    // The next run picks up the new size.
+    fn update_pty_size(&mut self) {
+        let Some(pty_size) = &self.pty_size else {
+            return;
+        };
+
+        let (cols, rows) = self.watch_area.get_content_size();
+        if cols == 0 || rows == 0 {
+            return;
+        }
+
+        let size = PtySize { cols, rows };
+        if *pty_size.read().unwrap() != size {
+            *pty_size.write().unwrap() = size;
+        }
     }
 }
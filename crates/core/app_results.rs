@@ -2,15 +2,21 @@
 // Use of this source code is governed by an MIT license
 // that can be found in the LICENSE file.
 
-use super::{App, ResultItems};
+use super::{ActiveWindow, App, ResultItems};
+use crate::baseline::Baseline;
 use crate::common::{logging_result, OutputMode};
 use crate::exec::{exec_after_command, CommandResult};
 use crate::history::{History, HistorySummary};
 use crate::output::WatchRenderData;
+use crate::stable::Stability;
 use hwatch_diffmode::text_eq_ignoring_space_blocks;
 use similar::{ChangeTag, TextDiff};
 use std::collections::HashMap;
 use std::thread;
+use std::time::{Duration, Instant};
+
+const FLASH_DURATION: Duration = Duration::from_millis(1500);
+const FLASH_BLINK: Duration = Duration::from_millis(250);
 
 impl App<'_> {
     pub(super) fn set_output_data(&mut self, num: usize) {
@@ -41,7 +47,12 @@ impl App<'_> {
             .unwrap()
             .get_support_only_diffline();
 
-        if previous_dst > 0 {
+        // a base that has since been dropped from the history is ignored
+        if let Some(base) = self.diff_base.filter(|base| results.contains_key(base)) {
+            src = &results[&base].command_result;
+        } else if let Some(baseline) = self.baseline.as_ref().and_then(Baseline::get) {
+            src = baseline;
+        } else if previous_dst > 0 {
             src = &results[&previous_dst].command_result;
         } else if previous_dst == 0 && self.is_only_diffline && support_only_diffline {
             src = &results[&0].command_result;
@@ -106,8 +117,19 @@ impl App<'_> {
         tmp_history.push(History {
             timestamp: "latest                 ".to_string(),
             status: results[&latest_num].command_result.status,
+            timed_out: results[&latest_num].command_result.timed_out,
+            exit_code: None,
+            signal: None,
+            duration: None,
+            cpu_time: None,
+            max_rss: None,
+            truncated: false,
             num: 0,
             summary: HistorySummary::init(),
+            flaps: 0,
+            runs: 0,
+            last_seen: String::new(),
+            stdin_change: None,
         });
 
         let mut new_select: Option<usize> = None;
@@ -144,12 +166,16 @@ impl App<'_> {
             }
 
             if is_push {
-                tmp_history.push(History {
-                    timestamp: result.command_result.timestamp.clone(),
-                    status: result.command_result.status,
-                    num: *key as u16,
-                    summary: result.summary.clone(),
-                });
+                let mut history = History::from_result(
+                    *key as u16,
+                    &result.command_result,
+                    result.summary.clone(),
+                );
+                history.flaps = result.flaps;
+                history.runs = result.runs;
+                history.last_seen = result.last_seen.clone();
+                history.stdin_change = get_stdin_change(results, *key);
+                tmp_history.push(history);
 
                 tmp_results.insert(*key, result.clone());
 
@@ -183,6 +209,14 @@ impl App<'_> {
         result: CommandResult,
         is_running_app: bool,
     ) -> bool {
+        // A heartbeat from the logfile only counts a run of the latest result.
+        if result.heartbeat {
+            if !self.results.is_empty() {
+                self.add_heartbeat(&result, false);
+            }
+            return false;
+        }
+
         self.header_area.set_current_result(result.clone());
         self.header_area.update();
 
@@ -198,10 +232,46 @@ impl App<'_> {
             latest_result = self.results[&latest_num].command_result.clone();
         }
 
-        if command_results_equivalent(&latest_result, &result, self.ignore_spaceblock) {
-            return false;
+        // With a baseline, a run that differs from it is a change even when
+        // it repeats the run before it.
+        let drifted = match self.baseline.as_mut() {
+            Some(baseline) => {
+                baseline.observe(&result);
+                !baseline.matches(&result, self.output_mode, self.ignore_spaceblock)
+            }
+            None => false,
+        };
+
+        let unchanged = command_results_equivalent(&latest_result, &result, self.ignore_spaceblock);
+        if unchanged {
+            self.add_heartbeat(&result, is_running_app);
+            if drifted && is_running_app {
+                let baseline = self.baseline.as_ref().and_then(Baseline::get).cloned();
+                self.spawn_after_command(baseline.unwrap_or_default(), result.clone());
+            }
         }
 
+        let ignore_spaceblock = self.ignore_spaceblock;
+        let result = match self.stable.as_mut() {
+            // Results loaded from a logfile were already settled, and the
+            // first run has nothing to flap against.
+            Some(stable) if is_running_app && self.results.len() > 1 => {
+                match stable.check(result, unchanged, |a, b| {
+                    command_results_equivalent(a, b, ignore_spaceblock)
+                }) {
+                    Stability::Changed(result) => *result,
+                    Stability::Flap => {
+                        self.add_flap();
+                        return unchanged && drifted;
+                    }
+                    Stability::Unchanged => return drifted,
+                    Stability::Pending => return false,
+                }
+            }
+            _ if unchanged => return drifted,
+            _ => result,
+        };
+
         let stdout_latest_index = get_results_latest_index(&self.results_stdout);
         let stdout_latest_result = self.results_stdout[&stdout_latest_index]
             .command_result
@@ -212,14 +282,19 @@ impl App<'_> {
             .command_result
             .clone();
 
+        // With a baseline, only a run that differs from it is a change, and
+        // the summary counts are taken against it.
+        let changed = self.baseline.is_none() || drifted;
+        let baseline = self.baseline.as_ref().and_then(Baseline::get).cloned();
+
         let (output_result_items, stdout_result_items, stderr_result_items) = gen_result_items(
             result,
             self.summary_enabled,
             self.enable_summary_char,
             self.ignore_spaceblock,
-            &latest_result,
-            &stdout_latest_result,
-            &stderr_latest_result,
+            baseline.as_ref().unwrap_or(&latest_result),
+            baseline.as_ref().unwrap_or(&stdout_latest_result),
+            baseline.as_ref().unwrap_or(&stderr_latest_result),
         );
 
         let _ = self.update_result(
@@ -227,9 +302,101 @@ impl App<'_> {
             stdout_result_items,
             stderr_result_items,
             is_running_app,
+            changed,
         );
+        self.update_baseline_status();
 
-        true
+        changed
+    }
+
+    /// Show in the header whether the latest result differs from the baseline.
+    pub(super) fn update_baseline_status(&mut self) {
+        let latest_num = get_results_latest_index(&self.results);
+        let status = match (self.baseline.as_ref(), self.results.get(&latest_num)) {
+            (Some(baseline), Some(latest)) if latest_num > 0 && baseline.get().is_some() => {
+                Some(baseline.matches(
+                    &latest.command_result,
+                    self.output_mode,
+                    self.ignore_spaceblock,
+                ))
+            }
+            _ => None,
+        };
+        self.header_area.set_baseline(status);
+        self.header_area.update();
+    }
+
+    /// Count an unchanged run on the latest result, and log it with `--log-heartbeat`.
+    fn add_heartbeat(&mut self, result: &CommandResult, is_running_app: bool) {
+        for results in [
+            &mut self.results,
+            &mut self.results_stdout,
+            &mut self.results_stderr,
+        ] {
+            let latest_num = get_results_latest_index(results);
+            if let Some(items) = results.get_mut(&latest_num) {
+                items.runs += 1;
+                items.last_seen = result.timestamp.clone();
+            }
+        }
+
+        let results = match self.output_mode {
+            OutputMode::Output => &self.results,
+            OutputMode::Stdout => &self.results_stdout,
+            OutputMode::Stderr => &self.results_stderr,
+        };
+        let latest_num = get_results_latest_index(results);
+        if let Some(items) = results.get(&latest_num) {
+            self.history_area
+                .set_runs(latest_num, items.runs, &items.last_seen);
+        }
+
+        if self.log_heartbeat && is_running_app && !self.logfile.is_empty() {
+            let _ = logging_result(&self.logfile, &result.to_heartbeat());
+        }
+    }
+
+    /// Count a dropped change on the latest recorded result.
+    fn add_flap(&mut self) {
+        let latest_num = get_results_latest_index(&self.results);
+        if latest_num == 0 {
+            return;
+        }
+
+        let mut flaps = 0;
+        for results in [
+            &mut self.results,
+            &mut self.results_stdout,
+            &mut self.results_stderr,
+        ] {
+            if let Some(items) = results.get_mut(&latest_num) {
+                items.flaps += 1;
+                flaps = items.flaps;
+            }
+        }
+        self.history_area.set_flaps(latest_num, flaps);
+    }
+
+    /// Run `--aftercommand` in the background, if set, on a change from
+    /// `before_result` to `after_result`.
+    fn spawn_after_command(&self, before_result: CommandResult, after_result: CommandResult) {
+        if self.after_command.is_empty() {
+            return;
+        }
+
+        let after_command = self.after_command.clone();
+        let after_command_result_write_file = self.after_command_result_write_file;
+        let shell_command = self.after_command_shell_command.clone();
+
+        thread::spawn(move || {
+            exec_after_command(
+                shell_command,
+                after_command,
+                before_result,
+                after_result,
+                after_command_result_write_file,
+            );
+        });
     }
 
     pub(super) fn update_result(
@@ -238,6 +405,7 @@ impl App<'_> {
         stdout_result_items: ResultItems,
         stderr_result_items: ResultItems,
         is_running_app: bool,
+        is_changed: bool,
     ) -> bool {
         if self.results.is_empty() {
             self.results.insert(0, output_result_items.clone());
@@ -245,27 +413,17 @@ impl App<'_> {
             self.results_stderr.insert(0, stderr_result_items.clone());
         }
 
-        if !self.after_command.is_empty() && is_running_app {
-            let after_command = self.after_command.clone();
-
-            let results = self.results.clone();
-            let latest_num = results.len() - 1;
+        if is_running_app && is_changed {
+            let latest_num = self.results.len() - 1;
 
-            let before_result: CommandResult = self.results[&latest_num].command_result.clone();
+            let before_result: CommandResult = match self.baseline.as_ref().and_then(Baseline::get)
+            {
+                Some(baseline) => baseline.clone(),
+                None => self.results[&latest_num].command_result.clone(),
+            };
             let after_result = output_result_items.command_result.clone();
 
-            let after_command_result_write_file = self.after_command_result_write_file;
-            let shell_command = self.after_command_shell_command.clone();
-
-            thread::spawn(move || {
-                exec_after_command(
-                    shell_command,
-                    after_command.clone(),
-                    before_result,
-                    after_result,
-                    after_command_result_write_file,
-                );
-            });
+            self.spawn_after_command(before_result, after_result);
         }
 
         let insert_result = self.insert_result(
@@ -345,7 +503,8 @@ impl App<'_> {
             return;
         }
 
-        if !self.exit_on_change_armed {
+        // The first run is a change from nothing, unless it differs from a baseline.
+        if !self.exit_on_change_armed && self.baseline.is_none() {
             self.exit_on_change_armed = true;
             return;
         }
@@ -364,6 +523,58 @@ impl App<'_> {
         }
     }
 
+    pub(super) fn handle_exit_on_status(&mut self, status: bool) {
+        match self.exit_on_status {
+            Some(exit_on_status) if exit_on_status.matches(status) => {}
+            _ => return,
+        }
+
+        let reason = match self.last_exit_code {
+            0 => "The command succeeded.".to_string(),
+            code => format!("The command failed (exit {code})."),
+        };
+        self.finish_watch(reason);
+    }
+
+    pub(super) fn handle_exit_on_match(&mut self, matched: bool) {
+        if !matched {
+            return;
+        }
+
+        if let Some(exit_on_match) = &self.exit_on_match {
+            let reason = exit_on_match.describe();
+            self.finish_watch(reason);
+        }
+    }
+
+    /// Exit, or with `--until-pause` pause the command and show why.
+    fn finish_watch(&mut self, reason: String) {
+        if !self.pause_on_exit {
+            self.done = true;
+            return;
+        }
+
+        self.interval.write().unwrap().paused = true;
+        self.header_area.update();
+        self.finished_reason = reason;
+        self.flash_started = Some(Instant::now());
+        self.window = ActiveWindow::Finished;
+    }
+
+    /// The finished popup blinks for a moment so it is noticed.
+    pub(super) fn is_flashing(&self) -> bool {
+        matches!(self.flash_started, Some(started) if started.elapsed() < FLASH_DURATION)
+    }
+
+    pub(super) fn is_flash_on(&self) -> bool {
+        match self.flash_started {
+            Some(started) if self.is_flashing() => {
+                (started.elapsed().as_millis() / FLASH_BLINK.as_millis()).is_multiple_of(2)
+            }
+            _ => false,
+        }
+    }
+
     pub(super) fn insert_result(
         &mut self,
         output_result_items: ResultItems,
@@ -482,6 +693,27 @@ pub(super) fn get_results_latest_index(results: &HashMap<usize, ResultItems>) ->
     result_num
 }
 
+/// Stdin hash of a result, if it was fed a different `--stdin-file` content
+/// than the result before it.
+pub(super) fn get_stdin_change(
+    results: &HashMap<usize, ResultItems>,
+    index: usize,
+) -> Option<String> {
+    let stdin_hash = &results.get(&index)?.command_result.stdin_hash;
+    let previous = get_results_previous_index(results, index);
+    let previous_hash = match previous {
+        0 => None,
+        _ => results
+            .get(&previous)
+            .map(|item| &item.command_result.stdin_hash),
+    };
+
+    match stdin_hash.is_empty() || previous_hash == Some(stdin_hash) {
+        true => None,
+        false => Some(stdin_hash.clone()),
+    }
+}
+
 pub(super) fn get_results_previous_index(
     results: &HashMap<usize, ResultItems>,
     index: usize,
@@ -544,6 +776,9 @@ pub(super) fn gen_result_items(
         command_result: result.clone(),
         summary: HistorySummary::init(),
         diff_only_data: output_diff_only_data,
+        flaps: 0,
+        runs: 1,
+        last_seen: result.timestamp.clone(),
     };
     if summary_enabled {
         output_result_items.summary.calc(
@@ -563,6 +798,9 @@ pub(super) fn gen_result_items(
         command_result: result.clone(),
         summary: HistorySummary::init(),
         diff_only_data: stdout_diff_only_data,
+        flaps: 0,
+        runs: 1,
+        last_seen: result.timestamp.clone(),
     };
     if summary_enabled {
         stdout_result_items.summary.calc(
@@ -582,6 +820,9 @@ pub(super) fn gen_result_items(
         command_result: result.clone(),
         summary: HistorySummary::init(),
         diff_only_data: stderr_diff_only_data,
+        flaps: 0,
+        runs: 1,
+        last_seen: result.timestamp.clone(),
     };
     if summary_enabled {
         stderr_result_items.summary.calc(
@@ -606,6 +847,9 @@ pub(super) fn command_results_equivalent(
 ) -> bool {
     before.command == after.command
         && before.status == after.status
+        && before.timed_out == after.timed_out
+        && before.exit_code == after.exit_code
+        && before.signal == after.signal
         && text_eq_ignoring_space_blocks(
             &before.get_output(),
             &after.get_output(),
@@ -0,0 +1,95 @@
+// Copyright (c) 2026 Blacknon. All rights reserved.
+// Use of this source code is governed by an MIT license
+// that can be found in the LICENSE file.
+
+use crate::common::OutputMode;
+use crate::exec::CommandResult;
+use hwatch_diffmode::text_eq_ignoring_space_blocks;
+
+/// A fixed result that new runs are compared against, in place of the run
+/// before them. A run that differs from it counts as a change.
+#[derive(Clone, Default)]
+pub struct Baseline {
+    result: Option<CommandResult>,
+}
+
+impl Baseline {
+    /// Pin `result`, or the first run seen when `None`.
+    pub fn new(result: Option<CommandResult>) -> Self {
+        Self { result }
+    }
+
+    /// Expected text, e.g. read from a file. It stands for whichever of
+    /// output, stdout and stderr is watched.
+    pub fn from_text(text: Vec<u8>) -> Self {
+        let result = CommandResult::default()
+            .set_output(text.clone())
+            .set_stdout(text.clone())
+            .set_stderr(text);
+        Self::new(Some(result))
+    }
+
+    /// Pin `result` if nothing is pinned yet.
+    pub fn observe(&mut self, result: &CommandResult) {
+        if self.result.is_none() {
+            self.result = Some(result.clone());
+        }
+    }
+
+    pub fn get(&self) -> Option<&CommandResult> {
+        self.result.as_ref()
+    }
+
+    /// Whether `result` shows the same text as the baseline for `output_mode`.
+    /// Nothing differs from a baseline that is not pinned yet.
+    pub fn matches(
+        &self,
+        result: &CommandResult,
+        output_mode: OutputMode,
+        ignore_spaceblock: bool,
+    ) -> bool {
+        let Some(baseline) = &self.result else {
+            return true;
+        };
+        let text = |result: &CommandResult| match output_mode {
+            OutputMode::Output => result.get_output(),
+            OutputMode::Stdout => result.get_stdout(),
+            OutputMode::Stderr => result.get_stderr(),
+        };
+
+        text_eq_ignoring_space_blocks(&text(baseline), &text(result), ignore_spaceblock)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(stdout: &str, stderr: &str) -> CommandResult {
+        CommandResult::default()
+            .set_output(format!("{stdout}{stderr}").into_bytes())
+            .set_stdout(stdout.as_bytes().to_vec())
+            .set_stderr(stderr.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn the_first_run_is_pinned_until_replaced() {
+        let mut baseline = Baseline::default();
+        assert!(baseline.matches(&run("a", ""), OutputMode::Output, false));
+
+        baseline.observe(&run("a", ""));
+        baseline.observe(&run("b", ""));
+        assert!(baseline.matches(&run("a", ""), OutputMode::Output, false));
+        assert!(!baseline.matches(&run("b", ""), OutputMode::Output, false));
+    }
+
+    #[test]
+    fn expected_text_is_compared_with_the_watched_stream() {
+        let baseline = Baseline::from_text(b"ok  1".to_vec());
+        let result = run("ok 1", "warn");
+
+        assert!(!baseline.matches(&result, OutputMode::Stdout, false));
+        assert!(baseline.matches(&result, OutputMode::Stdout, true));
+        assert!(!baseline.matches(&result, OutputMode::Stderr, true));
+    }
+}
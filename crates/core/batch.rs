@@ -3,14 +3,18 @@
 // that can be found in the LICENSE file.
 
 use crossbeam_channel::Receiver;
+use std::fs;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::{collections::HashMap, io};
 
-use crate::common::{logging_result, OutputMode};
+use crate::baseline::Baseline;
+use crate::common::{exit_code, logging_result, ExitOnMatch, ExitOnStatus, OutputMode};
 use crate::event::AppEvent;
 use crate::exec::{exec_after_command, CommandResult};
 use crate::output;
+use crate::stable::{Stability, StableFilter};
 
 use hwatch_diffmode::{text_eq_ignoring_space_blocks, DiffMode};
 
@@ -40,12 +44,36 @@ pub struct Batch {
     ///
     exit_on_change_armed: bool,
 
+    ///
+    exit_on_status: Option<ExitOnStatus>,
+
+    ///
+    exit_on_match: Option<ExitOnMatch>,
+
+    ///
+    stable: Option<StableFilter>,
+
+    /// compare runs with this instead of the previous run
+    baseline: Option<Baseline>,
+
+    /// file the output of every run is asserted against, with its contents
+    expect: Option<(PathBuf, Baseline)>,
+
+    /// rewrite the expected file instead of failing
+    update_expected: bool,
+
+    /// a run did not match the expected file
+    is_expect_failed: bool,
+
     ///
     is_reverse: bool,
 
     ///
     results: HashMap<usize, CommandResult>,
 
+    /// exit code of the most recent run, changed or not
+    last_exit_code: i32,
+
     ///
     output_mode: OutputMode,
 
@@ -64,6 +92,9 @@ pub struct Batch {
     ///
     logfile: String,
 
+    ///
+    log_heartbeat: bool,
+
     ///
     printer: output::Printer,
 
@@ -87,20 +118,30 @@ impl Batch {
             is_beep: false,
             exit_on_change: None,
             exit_on_change_armed: false,
+            exit_on_status: None,
+            exit_on_match: None,
+            stable: None,
+            baseline: None,
+            expect: None,
+            update_expected: false,
+            is_expect_failed: false,
             is_reverse: false,
             results: HashMap::new(),
+            last_exit_code: 0,
             output_mode: OutputMode::Output,
             diff_mode: 0,
             diff_modes,
             is_only_diffline: false,
             ignore_spaceblock: false,
             logfile: "".to_string(),
+            log_heartbeat: false,
             printer: output::Printer::new(mutex_diff_mode),
             rx,
         }
     }
 
-    pub fn run(&mut self) -> io::Result<()> {
+    /// Returns the exit code of the last run.
+    pub fn run(&mut self) -> io::Result<i32> {
         self.printer
             .set_batch(true)
             .set_color(self.is_color)
@@ -113,23 +154,33 @@ impl Batch {
 
         loop {
             if matches!(self.exit_on_change, Some(0)) {
-                return Ok(());
+                return Ok(self.exit_code());
             }
             match self.rx.recv() {
                 // Get command result.
                 Ok(AppEvent::OutputUpdate(exec_result)) => {
-                    let changed = self.update_result(exec_result);
+                    self.last_exit_code = exit_code(&exec_result);
+                    let status = exec_result.status;
+                    let matched = self.handle_exit_on_match(&exec_result);
+                    self.check_expect(&exec_result);
+                    let changed = self.update_result(*exec_result);
 
                     // beep
                     if changed && self.is_beep {
                         println!("\x07")
                     }
 
-                    if self.handle_exit_on_change(changed) {
-                        return Ok(());
+                    if self.handle_exit_on_change(changed)
+                        || self.handle_exit_on_status(status)
+                        || matched
+                    {
+                        return Ok(self.exit_code());
                     }
                 }
 
+                // The scheduler has used up --count or --duration.
+                Ok(AppEvent::Exit) => return Ok(self.exit_code()),
+
                 // Other event
                 Ok(_) => {}
 
@@ -140,6 +191,28 @@ impl Batch {
     }
 
     ///
+    /// Run `--aftercommand` in the background, if set, on a change from
+    /// `before_result` to `after_result`.
+    fn spawn_after_command(&self, before_result: CommandResult, after_result: CommandResult) {
+        if self.after_command.is_empty() {
+            return;
+        }
+
+        let after_command = self.after_command.clone();
+        let after_command_result_write_file = self.after_command_result_write_file;
+        let shell_command = self.after_command_shell_command.clone();
+
+        thread::spawn(move || {
+            exec_after_command(
+                shell_command,
+                after_command,
+                before_result,
+                after_result,
+                after_command_result_write_file,
+            );
+        });
+    }
+
     fn update_result(&mut self, _result: CommandResult) -> bool {
         // check results size.
         let mut latest_result = CommandResult::default();
@@ -154,38 +227,55 @@ impl Batch {
 
         // check result diff
         // NOTE: ここで実行結果の差分を比較している // 0.3.12リリースしたら消す
-        if command_results_equivalent(&latest_result, &_result, self.ignore_spaceblock) {
-            return false;
+        // With a baseline, a run that differs from it is a change even when
+        // it repeats the run before it.
+        let drifted = match self.baseline.as_mut() {
+            Some(baseline) => {
+                baseline.observe(&_result);
+                !baseline.matches(&_result, self.output_mode, self.ignore_spaceblock)
+            }
+            None => false,
+        };
+
+        let unchanged =
+            command_results_equivalent(&latest_result, &_result, self.ignore_spaceblock);
+        if unchanged && self.log_heartbeat && !self.logfile.is_empty() {
+            let _ = logging_result(&self.logfile, &_result.to_heartbeat());
+        }
+        if unchanged && drifted {
+            let baseline = self.baseline.as_ref().and_then(Baseline::get).cloned();
+            self.spawn_after_command(baseline.unwrap_or_default(), _result.clone());
         }
 
+        let ignore_spaceblock = self.ignore_spaceblock;
+        let _result = match self.stable.as_mut() {
+            Some(stable) if self.results.len() > 1 => {
+                match stable.check(_result, unchanged, |a, b| {
+                    command_results_equivalent(a, b, ignore_spaceblock)
+                }) {
+                    Stability::Changed(result) => *result,
+                    _ => return unchanged && drifted,
+                }
+            }
+            _ if unchanged => return drifted,
+            _ => _result,
+        };
+
         // logging result.
         if !self.logfile.is_empty() {
             let _ = logging_result(&self.logfile, &_result);
         }
 
-        if !self.after_command.is_empty() {
-            let after_command = self.after_command.clone();
-
-            let results = self.results.clone();
-            let latest_num = results.len() - 1;
-
-            let before_result = results[&latest_num].clone();
-            let after_result = _result.clone();
+        // With a baseline, only a run that differs from it is a change.
+        let changed = self.baseline.is_none() || drifted;
 
-            let after_command_result_write_file = self.after_command_result_write_file;
-            let shell_command = self.after_command_shell_command.clone();
-
-            {
-                thread::spawn(move || {
-                    exec_after_command(
-                        shell_command,
-                        after_command.clone(),
-                        before_result,
-                        after_result,
-                        after_command_result_write_file,
-                    );
-                });
-            }
+        if changed {
+            let latest_num = self.results.len() - 1;
+            let before_result = match self.baseline.as_ref().and_then(Baseline::get) {
+                Some(baseline) => baseline.clone(),
+                None => self.results[&latest_num].clone(),
+            };
+            self.spawn_after_command(before_result, _result.clone());
         }
 
         let should_print = self.should_print_for_output_mode(&latest_result, &_result);
@@ -193,12 +283,12 @@ impl Batch {
         // add result
         self.results.insert(self.results.len(), _result.clone());
 
-        // output result
-        if should_print {
+        // output result. With --expect, only the differences from the expected file are printed.
+        if should_print && self.expect.is_none() {
             self.printout_result();
         }
 
-        true
+        changed
     }
 
     fn should_print_for_output_mode(&self, before: &CommandResult, after: &CommandResult) -> bool {
@@ -227,11 +317,20 @@ impl Batch {
         let latest = self.results.len() - 1;
 
         // Switch the result depending on the output mode.
-        let dest = &self.results[&latest];
-        let timestamp_dst = &dest.timestamp;
+        let dest = self.results[&latest].clone();
 
         let previous = latest - 1;
-        let src = &self.results[&previous];
+        let src = match self.baseline.as_ref().and_then(Baseline::get) {
+            Some(baseline) => baseline.clone(),
+            None => self.results[&previous].clone(),
+        };
+
+        self.printout_diff(&dest, &src);
+    }
+
+    /// Print a split line with the timestamp of `dest`, then its diff from `src`.
+    fn printout_diff(&mut self, dest: &CommandResult, src: &CommandResult) {
+        let timestamp_dst = &dest.timestamp;
 
         // print split line
         if self.is_color {
@@ -252,6 +351,49 @@ impl Batch {
         println!("{:}", printout_data.join("\n"));
     }
 
+    /// Print how a run differs from the expected file, or rewrite the file
+    /// with its output when `update_expected` is set.
+    fn check_expect(&mut self, result: &CommandResult) {
+        let Some((path, expected)) = self.expect.as_mut() else {
+            return;
+        };
+        if expected.matches(result, self.output_mode, self.ignore_spaceblock) {
+            return;
+        }
+
+        if self.update_expected {
+            let text = match self.output_mode {
+                OutputMode::Output => result.get_output(),
+                OutputMode::Stdout => result.get_stdout(),
+                OutputMode::Stderr => result.get_stderr(),
+            };
+            match fs::write(&*path, &text) {
+                Ok(()) => {
+                    eprintln!("hwatch: updated {}", path.display());
+                    *expected = Baseline::from_text(text.into_bytes());
+                }
+                Err(err) => {
+                    eprintln!("hwatch: --update-expected {}: {err}", path.display());
+                    self.is_expect_failed = true;
+                }
+            }
+            return;
+        }
+
+        self.is_expect_failed = true;
+        if let Some(expected) = expected.get().cloned() {
+            self.printout_diff(result, &expected);
+        }
+    }
+
+    /// The exit code of the last run, or 1 once a run did not match `--expect`.
+    fn exit_code(&self) -> i32 {
+        match self.is_expect_failed {
+            true => 1,
+            false => self.last_exit_code,
+        }
+    }
+
     ///
     pub fn set_after_command(mut self, after_command: String) -> Self {
         self.after_command = after_command;
@@ -293,6 +435,42 @@ impl Batch {
         self
     }
 
+    ///
+    pub fn set_exit_on_status(mut self, exit_on_status: Option<ExitOnStatus>) -> Self {
+        self.exit_on_status = exit_on_status;
+        self
+    }
+
+    ///
+    pub fn set_stable_for(mut self, runs: Option<u32>) -> Self {
+        self.stable = runs.map(StableFilter::new);
+        self
+    }
+
+    ///
+    pub fn set_baseline(mut self, baseline: Option<Baseline>) -> Self {
+        self.baseline = baseline;
+        self
+    }
+
+    ///
+    pub fn set_expect(mut self, expect: Option<(PathBuf, Vec<u8>)>) -> Self {
+        self.expect = expect.map(|(path, text)| (path, Baseline::from_text(text)));
+        self
+    }
+
+    ///
+    pub fn set_update_expected(mut self, update_expected: bool) -> Self {
+        self.update_expected = update_expected;
+        self
+    }
+
+    ///
+    pub fn set_exit_on_match(mut self, exit_on_match: Option<ExitOnMatch>) -> Self {
+        self.exit_on_match = exit_on_match;
+        self
+    }
+
     ///
     pub fn set_reverse(mut self, is_reverse: bool) -> Self {
         self.is_reverse = is_reverse;
@@ -327,12 +505,19 @@ impl Batch {
         self
     }
 
+    ///
+    pub fn set_log_heartbeat(mut self, log_heartbeat: bool) -> Self {
+        self.log_heartbeat = log_heartbeat;
+        self
+    }
+
     fn handle_exit_on_change(&mut self, changed: bool) -> bool {
         if self.exit_on_change.is_none() {
             return false;
         }
 
-        if !self.exit_on_change_armed {
+        // The first run is a change from nothing, unless it differs from a baseline.
+        if !self.exit_on_change_armed && self.baseline.is_none() {
             self.exit_on_change_armed = true;
             return false;
         }
@@ -350,6 +535,20 @@ impl Batch {
 
         false
     }
+
+    /// Every run counts here, not only the ones that changed the output.
+    fn handle_exit_on_status(&self, status: bool) -> bool {
+        matches!(self.exit_on_status, Some(exit_on_status) if exit_on_status.matches(status))
+    }
+
+    fn handle_exit_on_match(&self, result: &CommandResult) -> bool {
+        match &self.exit_on_match {
+            Some(exit_on_match) => {
+                exit_on_match.matches(result, self.output_mode, self.ignore_spaceblock)
+            }
+            None => false,
+        }
+    }
 }
 
 fn command_results_equivalent(
@@ -359,6 +558,9 @@ fn command_results_equivalent(
 ) -> bool {
     before.command == after.command
         && before.status == after.status
+        && before.timed_out == after.timed_out
+        && before.exit_code == after.exit_code
+        && before.signal == after.signal
         && text_eq_ignoring_space_blocks(
             &before.get_output(),
             &after.get_output(),
@@ -506,6 +708,21 @@ mod tests {
         assert!(!command_results_equivalent(&before, &after, false));
     }
 
+    #[test]
+    fn update_result_counts_only_changes_away_from_the_baseline() {
+        let mut batch = new_batch(OutputMode::Output).set_baseline(Some(Baseline::default()));
+        let run = |output: &str| CommandResult::default().set_output(output.as_bytes().to_vec());
+
+        assert!(!batch.update_result(run("golden")));
+        assert!(batch.update_result(run("drift")));
+        assert!(!batch.update_result(run("golden")));
+        assert!(batch.update_result(run("other drift")));
+        // Repeating a drifted run keeps signalling it.
+        assert!(batch.update_result(run("other drift")));
+        assert!(!batch.update_result(run("golden")));
+        assert!(!batch.update_result(run("golden")));
+    }
+
     #[test]
     fn update_result_logs_current_result_instead_of_previous_one() {
         let logfile = NamedTempFile::new().unwrap();
@@ -529,6 +746,29 @@ mod tests {
         assert!(loaded[0] == result);
     }
 
+    #[test]
+    fn update_result_logs_unchanged_runs_as_heartbeats() {
+        let logfile = NamedTempFile::new().unwrap();
+        let path = logfile.path().to_string_lossy().into_owned();
+        let mut batch = new_batch(OutputMode::Output)
+            .set_logfile(path.clone())
+            .set_log_heartbeat(true);
+        let result = CommandResult {
+            command: "echo same".to_string(),
+            ..CommandResult::default()
+        }
+        .set_output(b"same\n".to_vec());
+
+        assert!(batch.update_result(result.clone()));
+        assert!(!batch.update_result(result.clone()));
+
+        let loaded = load_logfile(&path, false).ok().unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert!(!loaded[0].heartbeat);
+        assert!(loaded[1].heartbeat);
+        assert_eq!(loaded[1].get_output(), "");
+    }
+
     #[cfg(not(skip_proptest_tests))]
     proptest! {
         #[test]
@@ -9,7 +9,11 @@ use clap::{
 use std::collections::HashSet;
 use std::env::args;
 use std::ffi::OsString;
+use std::str::FromStr;
 
+use crate::exec::PtySize;
+use crate::history::HistoryColumn;
+use crate::interval::CronSchedule;
 use crate::{common, HISTORY_LIMIT, SHELL_COMMAND};
 
 pub fn build_app() -> Command {
@@ -56,6 +60,82 @@ pub fn build_app() -> Command {
                 .default_missing_value("1")
                 .value_parser(clap::value_parser!(u32)),
         )
+        .arg(
+            Arg::new("stable_for")
+                .help("Only count a change once the new output has been seen in the given number of consecutive runs. Changes that flip back earlier are counted in the `flaps` history column.")
+                .long("stable-for")
+                .value_name("RUNS")
+                .num_args(1)
+                .value_parser(clap::value_parser!(u32).range(1..)),
+        )
+        .arg(
+            Arg::new("baseline")
+                .help("Compare every run with the contents of the file instead of the previous run. Diffs, summary counts, --chgexit, --beep and --aftercommand then mean \"differs from the file\"")
+                .long("baseline")
+                .value_name("FILE")
+                .num_args(1)
+                .value_hint(ValueHint::FilePath),
+        )
+        .arg(
+            Arg::new("baseline_first")
+                .help("Like --baseline, with the output of the first run as the baseline")
+                .long("baseline-first")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("baseline"),
+        )
+        .arg(
+            Arg::new("expect")
+                .help("In batch mode, compare every run with the contents of the file, print the diff of each run that differs, and exit with 1 if any did")
+                .long("expect")
+                .value_name("FILE")
+                .num_args(1)
+                .value_hint(ValueHint::FilePath)
+                .requires("batch")
+                .conflicts_with_all(["baseline", "baseline_first"]),
+        )
+        .arg(
+            Arg::new("update_expected")
+                .help("Rewrite the --expect file with the output of a run that differs, instead of failing")
+                .long("update-expected")
+                .action(ArgAction::SetTrue)
+                .requires("expect"),
+        )
+        .arg(
+            Arg::new("until_success")
+                .help("Exit as soon as the command succeeds (exit code 0)")
+                .long("until-success")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("until_failure"),
+        )
+        .arg(
+            Arg::new("until_failure")
+                .help("Exit as soon as the command fails, with the exit code of the failed run")
+                .long("until-failure")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("until_match")
+                .help("Exit as soon as the output matches the regex (checked against the selected --output, without color codes. `^` and `$` match at line boundaries)")
+                .long("until-match")
+                .value_name("REGEX")
+                .num_args(1)
+                .value_parser(parse_line_regex)
+                .conflicts_with("until_no_match"),
+        )
+        .arg(
+            Arg::new("until_no_match")
+                .help("Exit as soon as the output no longer matches the regex")
+                .long("until-no-match")
+                .value_name("REGEX")
+                .num_args(1)
+                .value_parser(parse_line_regex),
+        )
+        .arg(
+            Arg::new("until_pause")
+                .help("With the --until-* options, pause, flash and show a popup instead of exiting. only work in watch mode.")
+                .long("until-pause")
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("border")
                 .help("Surround each pane with a border frame")
@@ -157,6 +237,90 @@ pub fn build_app() -> Command {
                 .short('p')
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("pty_size")
+                .help("Fix the pseudo-TTY size instead of following the watch pane size.")
+                .long("pty-size")
+                .value_name("COLSxROWS")
+                .num_args(1)
+                .value_parser(PtySize::from_str)
+                .requires("use_pty"),
+        )
+        .arg(
+            Arg::new("cwd")
+                .help("Run the command in the given directory")
+                .long("cwd")
+                .value_name("DIR")
+                .num_args(1)
+                .value_hint(ValueHint::DirPath),
+        )
+        .arg(
+            Arg::new("env")
+                .help("Set an environment variable for the command. Can be given several times. Overrides --env-file.")
+                .long("env")
+                .value_name("KEY=VALUE")
+                .num_args(1)
+                .value_parser(common::parse_env_assignment)
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("env_file")
+                .help("Read environment variables for the command from a file of KEY=VALUE lines. Can be given several times.")
+                .long("env-file")
+                .value_name("FILE")
+                .num_args(1)
+                .value_hint(ValueHint::FilePath)
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("file")
+                .help("Watch the content of a file instead of running a command. The file is read on the interval, and also as soon as it changes on Linux.")
+                .long("file")
+                .value_name("FILE")
+                .num_args(1)
+                .value_hint(ValueHint::FilePath)
+                .conflicts_with_all(["command", "stdin_snapshots"]),
+        )
+        .arg(
+            Arg::new("stdin_snapshots")
+                .help("Watch a stream on stdin instead of running a command, split into snapshots by --snapshot-delimiter or --snapshot-idle")
+                .long("stdin-snapshots")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["command", "count", "duration"]),
+        )
+        .arg(
+            Arg::new("snapshot_delimiter")
+                .help("End a --stdin-snapshots snapshot at each line equal to the given text")
+                .long("snapshot-delimiter")
+                .value_name("LINE")
+                .num_args(1)
+                .allow_hyphen_values(true)
+                .requires("stdin_snapshots"),
+        )
+        .arg(
+            Arg::new("snapshot_idle")
+                .help("End a --stdin-snapshots snapshot once no line has arrived for the given time (e.g. `0.5`, `2s`). Defaults to 0.5 seconds without --snapshot-delimiter.")
+                .long("snapshot-idle")
+                .value_name("DURATION")
+                .num_args(1)
+                .value_parser(common::parse_duration)
+                .requires("stdin_snapshots"),
+        )
+        .arg(
+            Arg::new("stdin_file")
+                .help("Read the file on every run and pipe it to the command's stdin, also with --use-pty. Edits to the file are marked in the `stdin` history column.")
+                .long("stdin-file")
+                .value_name("FILE")
+                .num_args(1)
+                .value_hint(ValueHint::FilePath),
+        )
+        .arg(
+            Arg::new("max_output_bytes")
+                .help("Keep at most this many bytes of stdout and of stderr of each run. The rest is read and dropped, and the result is marked as truncated.")
+                .long("max-output-bytes")
+                .value_name("BYTES")
+                .value_parser(clap::value_parser!(u64).range(1..)),
+        )
         .arg(
             Arg::new("diff_output_only")
                 .help("Display only the lines with differences during `line` diff and `word` diff.")
@@ -195,6 +359,31 @@ pub fn build_app() -> Command {
                 .value_hint(ValueHint::FilePath)
                 .action(ArgAction::Append),
         )
+        .arg(
+            Arg::new("replay")
+                .help("Open the results recorded in a logfile, with every diff mode, filter and history key, without running a command or writing the file")
+                .long("replay")
+                .value_name("LOGFILE")
+                .num_args(1)
+                .value_hint(ValueHint::FilePath)
+                .conflicts_with_all(["command", "logfile", "file", "stdin_snapshots", "batch", "after_command"]),
+        )
+        .arg(
+            Arg::new("follow_log")
+                .help("Follow a logfile that another hwatch is writing, and show its records as they are added, without running a command or writing the file. Truncation and rotation of the file are followed.")
+                .long("follow-log")
+                .value_name("LOGFILE")
+                .num_args(1)
+                .value_hint(ValueHint::FilePath)
+                .conflicts_with_all(["command", "logfile", "file", "stdin_snapshots", "batch", "after_command", "replay"]),
+        )
+        .arg(
+            Arg::new("log_heartbeat")
+                .help("Also write unchanged runs to the logfile, as short heartbeat records without the output")
+                .long("log-heartbeat")
+                .action(ArgAction::SetTrue)
+                .requires("logfile"),
+        )
         .arg(
             Arg::new("force_logfile_overwrite")
                 .help("continue even if an existing logfile is empty or unreadable")
@@ -219,12 +408,120 @@ pub fn build_app() -> Command {
                 .value_parser(clap::value_parser!(f64))
                 .default_value("2"),
         )
+        .arg(
+            Arg::new("align")
+                .help("Run on wall-clock multiples of the interval, counted from local midnight (e.g. `-n 60 --align` runs at :00 of every minute)")
+                .long("align")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("cron")
+                .help("Run on the times matched by a cron expression (`minute hour day-of-month month day-of-week`) instead of the interval")
+                .long("cron")
+                .value_name("EXPR")
+                .num_args(1)
+                .value_parser(CronSchedule::parse)
+                .conflicts_with_all(["interval", "align"]),
+        )
+        .arg(
+            Arg::new("backoff_max")
+                .help("Stretch the interval after consecutive failures, up to the given seconds. A success goes back to the interval.")
+                .long("backoff-max")
+                .value_name("SECONDS")
+                .num_args(1)
+                .value_parser(clap::value_parser!(f64))
+                .conflicts_with("cron"),
+        )
+        .arg(
+            Arg::new("backoff_factor")
+                .help("Multiply the interval by this for each step of --backoff-max [default: 2]")
+                .long("backoff-factor")
+                .value_name("FACTOR")
+                .num_args(1)
+                .value_parser(clap::value_parser!(f64))
+                .requires("backoff_max"),
+        )
+        .arg(
+            Arg::new("backoff_unchanged")
+                .help("Also stretch the interval once the output has not changed for the given number of runs")
+                .long("backoff-unchanged")
+                .value_name("RUNS")
+                .num_args(1)
+                .value_parser(clap::value_parser!(u32).range(1..))
+                .requires("backoff_max"),
+        )
         .arg(
             Arg::new("precise")
                 .help("Attempt to run as close to the interval as possible, regardless of how long the command takes to run")
                 .long("precise")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("overlap")
+                .help("What to do when a run is still going at the next --precise tick: skip the tick, queue the run, kill the running command, or run in parallel")
+                .long("overlap")
+                .value_name("POLICY")
+                .num_args(1)
+                .value_parser(["skip", "queue", "kill", "parallel"])
+                .requires("precise"),
+        )
+        .arg(
+            Arg::new("watch_path")
+                .help("Also run the command when a file matching the glob changes. A directory watches everything below it. Can be given several times.")
+                .long("watch-path")
+                .value_name("GLOB")
+                .num_args(1)
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("watch_path_only")
+                .help("Run the command only when a --watch-path file changes, not on the interval")
+                .long("watch-path-only")
+                .action(ArgAction::SetTrue)
+                .requires("watch_path"),
+        )
+        .arg(
+            Arg::new("timeout")
+                .help("Kill the command (and its process group) if it runs longer than the given seconds")
+                .long("timeout")
+                .value_name("SECONDS")
+                .num_args(1)
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("retries")
+                .help("Run a failed command again up to the given number of times before its result is recorded. Only the last attempt is recorded.")
+                .long("retries")
+                .value_name("N")
+                .num_args(1)
+                .value_parser(clap::value_parser!(u32)),
+        )
+        .arg(
+            Arg::new("retry_delay")
+                .help("Wait the given time (e.g. `0.5`, `2s`) before each --retries attempt")
+                .long("retry-delay")
+                .value_name("DURATION")
+                .num_args(1)
+                .default_value("1")
+                .requires("retries")
+                .value_parser(crate::common::parse_duration),
+        )
+        .arg(
+            Arg::new("count")
+                .help("Exit after the command has run the given number of times. The exit code is that of the last run.")
+                .long("count")
+                .value_name("N")
+                .num_args(1)
+                .value_parser(clap::value_parser!(u64).range(1..)),
+        )
+        .arg(
+            Arg::new("duration")
+                .help("Exit once the given time has passed (e.g. `90`, `30s`, `10m`, `1h`). A run in progress is allowed to finish. The exit code is that of the last run.")
+                .long("duration")
+                .value_name("DURATION")
+                .num_args(1)
+                .value_parser(crate::common::parse_duration),
+        )
         .arg(
             Arg::new("limit")
                 .help("Set the number of history records to keep. only work in watch mode. Set `0` for unlimited recording.")
@@ -268,6 +565,15 @@ pub fn build_app() -> Command {
                 .default_value("output")
                 .action(ArgAction::Append),
         )
+        .arg(
+            Arg::new("history_column")
+                .help("Add a column to the history pane. Can be given several times or as a comma separated list.")
+                .long("history-column")
+                .value_name("COLUMN")
+                .value_delimiter(',')
+                .value_parser(["exit", "duration", "flaps", "runs", "cpu", "rss", "stdin", "truncated"])
+                .action(ArgAction::Append),
+        )
         .arg(
             Arg::new("keymap")
                 .help("Add keymap")
@@ -277,6 +583,37 @@ pub fn build_app() -> Command {
         )
 }
 
+/// Columns of the history pane: the ones given with `--history-column`, and
+/// the ones the other options fill in.
+pub fn get_history_columns(matcher: &clap::ArgMatches) -> Vec<HistoryColumn> {
+    let mut columns: Vec<HistoryColumn> = matcher
+        .get_many::<String>("history_column")
+        .unwrap_or_default()
+        .filter_map(|name| HistoryColumn::from_name(name))
+        .collect();
+
+    let needed = [
+        // run counter, for --log-heartbeat
+        (matcher.get_flag("log_heartbeat"), HistoryColumn::Runs),
+        // flap counter, for --stable-for
+        (matcher.contains_id("stable_for"), HistoryColumn::Flaps),
+        // stdin edits, for --stdin-file
+        (matcher.contains_id("stdin_file"), HistoryColumn::Stdin),
+        // cut output, for --max-output-bytes
+        (
+            matcher.contains_id("max_output_bytes"),
+            HistoryColumn::Truncated,
+        ),
+    ];
+    for (is_needed, column) in needed {
+        if is_needed && !columns.contains(&column) {
+            columns.push(column);
+        }
+    }
+
+    columns
+}
+
 pub fn get_clap_matcher(cmd_app: Command) -> clap::ArgMatches {
     let mut os_args = std::env::args_os();
     let program = os_args
@@ -330,6 +667,11 @@ pub fn should_continue_with_unreadable_logfile(
     common::confirm_yes_default("Log to the same file?")
 }
 
+/// Regex for `--until-match`, with `^` and `$` matching at each line.
+fn parse_line_regex(value: &str) -> Result<regex::Regex, regex::Error> {
+    regex::RegexBuilder::new(value).multi_line(true).build()
+}
+
 fn builtin_diff_mode_names() -> HashSet<String> {
     HashSet::from([
         "none".to_string(),
@@ -424,7 +766,10 @@ fn normalize_args(args: Vec<OsString>) -> Vec<OsString> {
 
 #[cfg(test)]
 mod tests {
-    use super::{build_app, normalize_args, should_continue_with_unreadable_logfile};
+    use super::{
+        build_app, get_history_columns, normalize_args, should_continue_with_unreadable_logfile,
+    };
+    use crate::history::HistoryColumn;
     use std::ffi::OsString;
 
     #[test]
@@ -583,4 +928,37 @@ mod tests {
         assert!(!should_continue_with_unreadable_logfile(false, false));
         assert!(should_continue_with_unreadable_logfile(true, false));
     }
+
+    #[test]
+    fn history_columns_add_the_ones_other_options_need() {
+        let matches = build_app()
+            .try_get_matches_from(["hwatch", "echo", "hi"])
+            .unwrap();
+        assert!(get_history_columns(&matches).is_empty());
+
+        let matches = build_app()
+            .try_get_matches_from(["hwatch", "-l", "run.log", "--log-heartbeat", "echo", "hi"])
+            .unwrap();
+        assert_eq!(get_history_columns(&matches), vec![HistoryColumn::Runs]);
+
+        let matches = build_app()
+            .try_get_matches_from([
+                "hwatch",
+                "--history-column",
+                "exit,runs",
+                "--stable-for",
+                "2",
+                "echo",
+                "hi",
+            ])
+            .unwrap();
+        assert_eq!(
+            get_history_columns(&matches),
+            vec![
+                HistoryColumn::Exit,
+                HistoryColumn::Runs,
+                HistoryColumn::Flaps
+            ]
+        );
+    }
 }
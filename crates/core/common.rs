@@ -3,17 +3,21 @@
 // that can be found in the LICENSE file.
 
 // module
-use chrono::Local;
+use chrono::{Local, NaiveDateTime};
 use serde_json::Deserializer;
 use std::error::Error;
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, BufReader, Write};
+use std::time::Duration;
 
+use hwatch_diffmode::normalize_space_blocks;
+use regex::Regex;
 use tui::layout::{Constraint, Direction, Layout, Rect};
 use tui::style::Color;
 
 // local module
 use crate::exec::{CommandResult, CommandResultData};
+use crate::hwatch_ansi::get_ansi_strip_str;
 
 ///
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -23,10 +27,202 @@ pub enum OutputMode {
     Stderr,
 }
 
+/// Run outcome that ends the watch (`--until-success` / `--until-failure`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitOnStatus {
+    Success,
+    Failure,
+}
+
+impl ExitOnStatus {
+    pub fn matches(&self, status: bool) -> bool {
+        match self {
+            ExitOnStatus::Success => status,
+            ExitOnStatus::Failure => !status,
+        }
+    }
+}
+
+/// Output pattern that ends the watch (`--until-match` / `--until-no-match`).
+#[derive(Clone, Debug)]
+pub struct ExitOnMatch {
+    regex: Regex,
+
+    /// exit when the output matches, or when it stops matching
+    matching: bool,
+}
+
+impl ExitOnMatch {
+    pub fn new(regex: Regex, matching: bool) -> Self {
+        Self { regex, matching }
+    }
+
+    /// Check the text shown for `output_mode`, without color codes and with
+    /// runs of spaces collapsed when `ignore_spaceblock` is set.
+    pub fn matches(
+        &self,
+        result: &CommandResult,
+        output_mode: OutputMode,
+        ignore_spaceblock: bool,
+    ) -> bool {
+        let text = match output_mode {
+            OutputMode::Output => result.get_output(),
+            OutputMode::Stdout => result.get_stdout(),
+            OutputMode::Stderr => result.get_stderr(),
+        };
+        let mut text = get_ansi_strip_str(&text);
+        if ignore_spaceblock {
+            text = normalize_space_blocks(&text);
+        }
+
+        self.regex.is_match(&text) == self.matching
+    }
+
+    pub fn describe(&self) -> String {
+        match self.matching {
+            true => format!("The output matches '{}'.", self.regex),
+            false => format!("The output no longer matches '{}'.", self.regex),
+        }
+    }
+}
+
+// Format of the timestamps recorded in CommandResult.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+
 ///
 pub fn now_str() -> String {
     let date = Local::now();
-    date.format("%Y-%m-%d %H:%M:%S%.3f").to_string()
+    date.format(TIMESTAMP_FORMAT).to_string()
+}
+
+/// Elapsed time between two timestamps created by `now_str()`.
+pub fn duration_between(start: &str, end: &str) -> Option<Duration> {
+    let start = NaiveDateTime::parse_from_str(start, TIMESTAMP_FORMAT).ok()?;
+    let end = NaiveDateTime::parse_from_str(end, TIMESTAMP_FORMAT).ok()?;
+    (end - start).to_std().ok()
+}
+
+/// Short human readable duration, at most 7 characters wide.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..=9 => format!("{:.3}s", duration.as_secs_f64()),
+        10..=59 => format!("{:.2}s", duration.as_secs_f64()),
+        60..=3599 => format!("{}m{:02}s", secs / 60, secs % 60),
+        _ => format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+/// Short human readable size of a value in KiB, at most 7 characters wide.
+pub fn format_kib(kib: u64) -> String {
+    let mib = kib as f64 / 1024.0;
+    match kib {
+        0..=1023 => format!("{kib}K"),
+        1024..=1_048_575 => format!("{mib:.1}M"),
+        _ => format!("{:.2}G", mib / 1024.0),
+    }
+}
+
+/// Parse a duration such as `90`, `1.5s`, `10m`, `2h` or `1d`. A bare
+/// number is seconds.
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let (number, unit) = match text.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(index) => text.split_at(index),
+        None => (text, "s"),
+    };
+
+    let seconds = match unit {
+        "s" => 1.0,
+        "m" => 60.0,
+        "h" => 3600.0,
+        "d" => 86400.0,
+        _ => return Err(format!("unknown unit '{unit}' (use s, m, h or d)")),
+    };
+
+    match number.parse::<f64>() {
+        Ok(number) if number.is_finite() && number > 0.0 => {
+            Ok(Duration::from_secs_f64(number * seconds))
+        }
+        _ => Err(format!("'{text}' is not a positive duration")),
+    }
+}
+
+/// Parse a `KEY=VALUE` environment assignment.
+pub fn parse_env_assignment(text: &str) -> Result<(String, String), String> {
+    match text.split_once('=') {
+        Some((key, value)) if !key.is_empty() && !key.contains(char::is_whitespace) => {
+            Ok((key.to_string(), value.to_string()))
+        }
+        _ => Err(format!("'{text}' is not a KEY=VALUE assignment")),
+    }
+}
+
+/// Parse the contents of an env file: one `KEY=VALUE` per line, with optional
+/// `export ` prefixes, quoted values, blank lines and `#` comments.
+pub fn parse_env_file(text: &str) -> Result<Vec<(String, String)>, String> {
+    let mut env = vec![];
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line).trim_start();
+
+        let (key, value) =
+            parse_env_assignment(line).map_err(|err| format!("line {}: {err}", number + 1))?;
+        let value = value.trim();
+        let value = match value.as_bytes() {
+            [b'"', .., b'"'] | [b'\'', .., b'\''] => &value[1..value.len() - 1],
+            _ => value,
+        };
+        env.push((key, value.to_string()));
+    }
+    Ok(env)
+}
+
+/// Process exit code that mirrors how a run ended: `0` on success, the
+/// command's own code, `124` on `--timeout` (like timeout(1)) or `128 + signal`.
+pub fn exit_code(result: &CommandResult) -> i32 {
+    if result.status {
+        return 0;
+    }
+    if result.timed_out {
+        return 124;
+    }
+
+    match (result.exit_code, result.signal) {
+        (Some(code), _) if code > 0 => code,
+        (_, Some(signal)) => 128 + signal,
+        _ => 1,
+    }
+}
+
+/// Text for how a run ended: the exit code, the signal name, or `timeout`.
+/// Returns an empty string when nothing is known (e.g. old logfiles).
+pub fn exit_status_text(timed_out: bool, exit_code: Option<i32>, signal: Option<i32>) -> String {
+    if timed_out {
+        return "timeout".to_string();
+    }
+
+    match (exit_code, signal) {
+        (Some(code), _) => code.to_string(),
+        (None, Some(signal)) => signal_name(signal),
+        (None, None) => String::new(),
+    }
+}
+
+#[cfg(unix)]
+fn signal_name(signal: i32) -> String {
+    match nix::sys::signal::Signal::try_from(signal) {
+        Ok(signal) => signal.as_str().to_string(),
+        Err(_) => format!("SIG{signal}"),
+    }
+}
+
+#[cfg(not(unix))]
+fn signal_name(signal: i32) -> String {
+    format!("SIG{signal}")
 }
 
 pub enum LoadLogfileError {
@@ -275,6 +471,17 @@ mod tests {
             timestamp: "2026-04-08 12:00:00.000".to_string(),
             command: "echo test".to_string(),
             status: true,
+            timed_out: false,
+            exit_code: Some(0),
+            signal: None,
+            start_time: "2026-04-08 11:59:59.900".to_string(),
+            end_time: "2026-04-08 12:00:00.000".to_string(),
+            trigger: "".to_string(),
+            heartbeat: false,
+            rusage: None,
+            stdin_hash: "".to_string(),
+            truncated: false,
+            attempts: 1,
             is_compress: false,
             output: vec![],
             stdout: vec![],
@@ -292,6 +499,149 @@ mod tests {
         assert_eq!(loaded.len(), 1);
         assert!(loaded[0] == result);
         assert_eq!(loaded[0].timestamp, result.timestamp);
+        assert_eq!(loaded[0].exit_code, Some(0));
+        assert_eq!(loaded[0].start_time, result.start_time);
+        assert_eq!(loaded[0].end_time, result.end_time);
+    }
+
+    #[test]
+    fn load_logfile_accepts_records_without_exit_details() {
+        let mut logfile = NamedTempFile::new().unwrap();
+        writeln!(
+            logfile,
+            r#"{{"timestamp":"2026-04-08 12:00:00.000","command":"echo test","status":false,"output":"x","stdout":"x","stderr":""}}"#
+        )
+        .unwrap();
+
+        let loaded = load_logfile(logfile.path().to_str().unwrap(), false)
+            .ok()
+            .unwrap();
+
+        assert_eq!(loaded.len(), 1);
+        assert!(!loaded[0].status);
+        assert!(!loaded[0].timed_out);
+        assert_eq!(loaded[0].exit_code, None);
+        assert_eq!(loaded[0].signal, None);
+        assert_eq!(loaded[0].get_duration(), None);
+    }
+
+    #[test]
+    fn logging_result_omits_run_details_that_are_not_set() {
+        let logfile = NamedTempFile::new().unwrap();
+        let path = logfile.path().to_str().unwrap();
+        let result = CommandResult::default().set_output(b"x".to_vec());
+
+        logging_result(path, &result).unwrap();
+        let record = std::fs::read_to_string(path).unwrap();
+
+        for key in [
+            "timed_out",
+            "exit_code",
+            "signal",
+            "start_time",
+            "end_time",
+            "trigger",
+            "heartbeat",
+            "rusage",
+            "stdin_hash",
+            "truncated",
+            "attempts",
+        ] {
+            assert!(!record.contains(key), "{key} in {record}");
+        }
+    }
+
+    #[test]
+    fn format_duration_switches_units() {
+        assert_eq!(format_duration(Duration::from_millis(1234)), "1.234s");
+        assert_eq!(format_duration(Duration::from_millis(12345)), "12.35s");
+        assert_eq!(format_duration(Duration::from_secs(125)), "2m05s");
+        assert_eq!(format_duration(Duration::from_secs(7260)), "2h01m");
+    }
+
+    #[test]
+    fn format_kib_switches_units() {
+        assert_eq!(format_kib(812), "812K");
+        assert_eq!(format_kib(12_595), "12.3M");
+        assert_eq!(format_kib(3 * 1024 * 1024), "3.00G");
+    }
+
+    #[test]
+    fn parse_env_assignment_splits_at_the_first_equal_sign() {
+        assert_eq!(
+            parse_env_assignment("URL=http://host/?a=b"),
+            Ok(("URL".to_string(), "http://host/?a=b".to_string()))
+        );
+        assert_eq!(
+            parse_env_assignment("EMPTY="),
+            Ok(("EMPTY".to_string(), "".to_string()))
+        );
+        assert!(parse_env_assignment("NOVALUE").is_err());
+        assert!(parse_env_assignment("=value").is_err());
+        assert!(parse_env_assignment("KEY =value").is_err());
+    }
+
+    #[test]
+    fn parse_env_file_reads_dotenv_style_lines() {
+        let text = "# comment\n\nexport NAME=hwatch\nQUOTED=\"a b\"\nSINGLE='c'\n";
+        assert_eq!(
+            parse_env_file(text),
+            Ok(vec![
+                ("NAME".to_string(), "hwatch".to_string()),
+                ("QUOTED".to_string(), "a b".to_string()),
+                ("SINGLE".to_string(), "c".to_string()),
+            ])
+        );
+        assert_eq!(
+            parse_env_file("A=1\nbroken\n"),
+            Err("line 2: 'broken' is not a KEY=VALUE assignment".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_duration_accepts_units() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("1.5s"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("10m"), Ok(Duration::from_secs(600)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("1d"), Ok(Duration::from_secs(86400)));
+        assert!(parse_duration("10x").is_err());
+        assert!(parse_duration("0").is_err());
+        assert!(parse_duration("m").is_err());
+    }
+
+    #[test]
+    fn exit_code_follows_the_result() {
+        let mut result = CommandResult::default();
+        assert_eq!(exit_code(&result), 0);
+
+        result.status = false;
+        assert_eq!(exit_code(&result), 1);
+        result.exit_code = Some(3);
+        assert_eq!(exit_code(&result), 3);
+
+        result.exit_code = None;
+        result.signal = Some(9);
+        assert_eq!(exit_code(&result), 137);
+
+        result.timed_out = true;
+        assert_eq!(exit_code(&result), 124);
+    }
+
+    #[test]
+    fn exit_on_match_checks_the_selected_output() {
+        let result = CommandResult::default()
+            .set_output(b"web-1  \x1b[32mRunning\x1b[0m\n".to_vec())
+            .set_stdout(b"web-1  \x1b[32mRunning\x1b[0m\n".to_vec())
+            .set_stderr(b"warning\n".to_vec());
+
+        let running = ExitOnMatch::new(Regex::new("web-1 Running").unwrap(), true);
+        assert!(!running.matches(&result, OutputMode::Output, false));
+        assert!(running.matches(&result, OutputMode::Output, true));
+        assert!(!running.matches(&result, OutputMode::Stderr, true));
+
+        let pending = ExitOnMatch::new(Regex::new("Pending").unwrap(), false);
+        assert!(pending.matches(&result, OutputMode::Stdout, false));
     }
 
     #[test]
@@ -2,10 +2,19 @@
 // Use of this source code is governed by an MIT license
 // that can be found in the LICENSE file.
 
+use crate::common::OutputMode;
 use crate::exec::CommandResult;
 
 pub enum AppEvent {
-    OutputUpdate(CommandResult),
+    OutputUpdate(Box<CommandResult>),
+    /// A run with the given sequence number has started.
+    RunStarted(u64),
+    /// Output read from a run that is still going.
+    OutputChunk(u64, OutputMode, Vec<u8>),
+    /// A failed run is being retried, as attempt `.1` of `.2`.
+    RunRetrying(u64, u32, u32),
+    /// A run has ended. Its result (if any) is sent as `OutputUpdate` in start order.
+    RunFinished(u64),
     TerminalEvent(crossterm::event::Event),
     Redraw,
     ChangeFlagMouseEvent,
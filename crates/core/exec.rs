@@ -8,6 +8,12 @@
 
 // module
 use crossbeam_channel::Sender;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
 
 // local module
 use crate::common;
@@ -23,16 +29,46 @@ mod pty;
 mod result;
 
 pub use self::after_command::exec_after_command;
-use self::process::{create_exec_cmd_args, exec_command};
-pub use self::result::{CommandResult, CommandResultData};
+pub use self::process::OutputCallback;
+use self::process::{create_exec_cmd_args, exec_command, ProcessOptions};
+pub use self::pty::PtySize;
+pub use self::result::{CommandResult, CommandResultData, ResourceUsage};
+
+/// Told the number of the attempt about to start and the number of attempts
+/// allowed, when a failed run is retried.
+pub type RetryCallback = Arc<dyn Fn(u32, u32) + Send + Sync>;
+
+// How often a cancel is checked while waiting for the next attempt.
+const RETRY_POLL_INTERVAL: Duration = Duration::from_millis(10);
 
 // TODO(blacknon): commandは削除？
+#[derive(Clone)]
 pub struct ExecuteCommand {
     pub shell_command: String,
     pub command: Vec<String>,
     pub is_exec: bool,
     pub is_compress: bool,
     pub is_pty: bool,
+    pub pty_size: Arc<RwLock<PtySize>>,
+    pub timeout: Option<Duration>,
+    pub cancel: Option<Arc<AtomicBool>>,
+    pub on_output: Option<OutputCallback>,
+    /// working directory of the command, if not hwatch's own.
+    pub cwd: Option<PathBuf>,
+    /// variables added to the command's environment.
+    pub env: Vec<(String, String)>,
+    /// file read on every run and piped to the command's stdin.
+    pub stdin_file: Option<PathBuf>,
+    /// file whose content is taken as the output, instead of running a command.
+    pub file: Option<PathBuf>,
+    /// bytes kept of each output stream; the rest is dropped.
+    pub max_output_bytes: Option<usize>,
+    /// failed runs retried before the result is recorded.
+    pub retries: u32,
+    pub retry_delay: Duration,
+    pub on_retry: Option<RetryCallback>,
+    /// file change that started this run, if any.
+    pub trigger: String,
     pub tx: Sender<AppEvent>,
 }
 
@@ -45,13 +81,70 @@ impl ExecuteCommand {
             is_exec: false,
             is_compress: false,
             is_pty: false,
+            pty_size: Arc::new(RwLock::new(PtySize::default())),
+            timeout: None,
+            cancel: None,
+            on_output: None,
+            cwd: None,
+            env: vec![],
+            stdin_file: None,
+            file: None,
+            max_output_bytes: None,
+            retries: 0,
+            retry_delay: Duration::ZERO,
+            on_retry: None,
+            trigger: "".to_string(),
             tx,
         }
     }
 
     // exec command
     // TODO(blacknon): Resultからcommandを削除して、実行時はこのfunctionの引数として受け付けるように改修する？
-    pub fn exec_command(&mut self) {
+    /// Returns `None` when the run was cancelled through `cancel`.
+    pub fn exec(&mut self) -> Option<CommandResult> {
+        let attempts = self.retries.saturating_add(1);
+        let mut attempt = 1;
+        loop {
+            let mut result = self.exec_once()?;
+            result.attempts = attempt;
+            if result.status || attempt >= attempts {
+                return Some(result);
+            }
+
+            attempt += 1;
+            if !self.wait_retry_delay() {
+                return None;
+            }
+            if let Some(on_retry) = &self.on_retry {
+                on_retry(attempt, attempts);
+            }
+        }
+    }
+
+    /// Sleep for `retry_delay`. Returns false if the run was cancelled meanwhile.
+    fn wait_retry_delay(&self) -> bool {
+        let deadline = Instant::now() + self.retry_delay;
+        loop {
+            if self
+                .cancel
+                .as_ref()
+                .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+            {
+                return false;
+            }
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return true;
+            }
+            thread::sleep(left.min(RETRY_POLL_INTERVAL));
+        }
+    }
+
+    fn exec_once(&mut self) -> Option<CommandResult> {
+        if let Some(file) = &self.file {
+            return Some(self.read_file(file));
+        }
+
         let command_str = self.command.clone().join(" ");
 
         // create exec_commands...
@@ -61,46 +154,154 @@ impl ExecuteCommand {
             command_str.clone(),
         ) {
             Ok(exec_commands) => exec_commands,
-            Err(err) => {
-                let result = CommandResult {
-                    timestamp: common::now_str(),
-                    command: command_str,
-                    status: false,
-                    is_compress: self.is_compress,
-                    output: vec![],
-                    stdout: vec![],
-                    stderr: vec![],
+            Err(err) => return Some(self.error_result(command_str, err)),
+        };
+
+        // Read again on every run, so edits apply to the next run.
+        let stdin = match &self.stdin_file {
+            Some(path) => match std::fs::read(path) {
+                Ok(stdin) => Some(stdin),
+                Err(err) => {
+                    let err = format!("failed to read stdin file {}: {err}", path.display());
+                    return Some(self.error_result(command_str, err));
                 }
-                .set_output(format!("{err}\n").into_bytes())
-                .set_stderr(format!("{err}\n").into_bytes());
+            },
+            None => None,
+        };
 
-                let _ = self.tx.send(AppEvent::OutputUpdate(result));
-                return;
-            }
+        let start_time = common::now_str();
+        // The size is read on every run, so a resized pane applies to the next run.
+        let options = ProcessOptions {
+            is_pty: self.is_pty,
+            pty_size: *self.pty_size.read().unwrap(),
+            timeout: self.timeout,
+            cancel: self.cancel.as_ref(),
+            on_output: self.on_output.clone(),
+            cwd: self.cwd.as_deref(),
+            env: &self.env,
+            stdin: stdin.as_deref(),
+            max_output_bytes: self.max_output_bytes,
         };
+        let mut process_output = exec_command(&exec_commands, &options);
+        let end_time = common::now_str();
 
-        let (status, vec_output, vec_stdout, vec_stderr) =
-            exec_command(&exec_commands, self.is_pty);
+        if process_output.cancelled {
+            return None;
+        }
+
+        // Leave a note after whatever the command printed before it was killed.
+        if process_output.timed_out {
+            let timeout = self.timeout.unwrap_or_default().as_secs_f64();
+            let message = format!("hwatch: command timed out after {timeout}s\n");
+            process_output.output.extend_from_slice(message.as_bytes());
+            process_output.stderr.extend_from_slice(message.as_bytes());
+        }
 
         // Set result
         let result = CommandResult {
-            timestamp: common::now_str(),
+            timestamp: end_time.clone(),
             command: command_str,
-            status,
+            status: process_output.status,
+            timed_out: process_output.timed_out,
+            exit_code: process_output.exit_code,
+            signal: process_output.signal,
+            start_time,
+            end_time,
+            trigger: self.trigger.clone(),
+            heartbeat: false,
+            rusage: process_output.rusage,
+            stdin_hash: stdin.as_deref().map(content_hash).unwrap_or_default(),
+            truncated: process_output.truncated,
+            attempts: 1,
             is_compress: self.is_compress,
             output: vec![],
             stdout: vec![],
             stderr: vec![],
         }
-        .set_output(vec_output)
-        .set_stdout(vec_stdout)
-        .set_stderr(vec_stderr);
+        .set_output(process_output.output)
+        .set_stdout(process_output.stdout)
+        .set_stderr(process_output.stderr);
+
+        Some(result)
+    }
 
-        // Send result
-        let _ = self.tx.send(AppEvent::OutputUpdate(result));
+    /// Take the current content of `--file` as the output of a run.
+    fn read_file(&self, file: &Path) -> CommandResult {
+        let command_str = file.display().to_string();
+        let start_time = common::now_str();
+        let (data, truncated) = match read_capped(file, self.max_output_bytes) {
+            Ok(read) => read,
+            Err(err) => {
+                let err = format!("failed to read {command_str}: {err}");
+                return self.error_result(command_str, err);
+            }
+        };
+        let end_time = common::now_str();
+
+        CommandResult {
+            timestamp: end_time.clone(),
+            command: command_str,
+            start_time,
+            end_time,
+            trigger: self.trigger.clone(),
+            truncated,
+            is_compress: self.is_compress,
+            ..Default::default()
+        }
+        .set_output(data.clone())
+        .set_stdout(data)
+    }
+
+    /// Result of a run that could not be started.
+    fn error_result(&self, command_str: String, err: String) -> CommandResult {
+        let timestamp = common::now_str();
+        CommandResult {
+            timestamp: timestamp.clone(),
+            command: command_str,
+            status: false,
+            timed_out: false,
+            exit_code: None,
+            signal: None,
+            start_time: timestamp.clone(),
+            end_time: timestamp,
+            trigger: self.trigger.clone(),
+            heartbeat: false,
+            rusage: None,
+            stdin_hash: String::new(),
+            truncated: false,
+            attempts: 1,
+            is_compress: self.is_compress,
+            output: vec![],
+            stdout: vec![],
+            stderr: vec![],
+        }
+        .set_output(format!("{err}\n").into_bytes())
+        .set_stderr(format!("{err}\n").into_bytes())
     }
 }
 
+/// Read `file`, keeping at most `limit` bytes. Also tells whether anything was left out.
+fn read_capped(file: &Path, limit: Option<usize>) -> std::io::Result<(Vec<u8>, bool)> {
+    let Some(limit) = limit else {
+        return std::fs::read(file).map(|data| (data, false));
+    };
+
+    let mut data = Vec::new();
+    std::fs::File::open(file)?
+        .take(limit as u64 + 1)
+        .read_to_end(&mut data)?;
+    let truncated = data.len() > limit;
+    data.truncate(limit);
+    Ok((data, truncated))
+}
+
+/// Short hash of the data fed to the command, e.g. `1c291ca3`.
+fn content_hash(data: &[u8]) -> String {
+    let mut crc = flate2::Crc::new();
+    crc.update(data);
+    format!("{:08x}", crc.sum())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,6 +392,17 @@ mod tests {
             timestamp: "2026-04-08 12:00:00.000".to_string(),
             command: "echo hi".to_string(),
             status: true,
+            timed_out: false,
+            exit_code: Some(0),
+            signal: None,
+            start_time: "2026-04-08 11:59:59.500".to_string(),
+            end_time: "2026-04-08 12:00:00.000".to_string(),
+            trigger: "".to_string(),
+            heartbeat: false,
+            rusage: None,
+            stdin_hash: "".to_string(),
+            truncated: false,
+            attempts: 1,
             output: "joined".to_string(),
             stdout: "stdout".to_string(),
             stderr: "stderr".to_string(),
@@ -210,6 +422,17 @@ mod tests {
             timestamp: "2026-04-08 12:00:00.000".to_string(),
             command: "echo hi".to_string(),
             status: false,
+            timed_out: false,
+            exit_code: Some(2),
+            signal: None,
+            start_time: "2026-04-08 11:59:59.000".to_string(),
+            end_time: "2026-04-08 12:00:00.000".to_string(),
+            trigger: "".to_string(),
+            heartbeat: false,
+            rusage: None,
+            stdin_hash: "".to_string(),
+            truncated: false,
+            attempts: 1,
             is_compress: true,
             output: vec![],
             stdout: vec![],
@@ -225,6 +448,44 @@ mod tests {
         assert_eq!(exported.stdout, "out");
         assert_eq!(exported.stderr, "err");
         assert!(!exported.status);
+        assert_eq!(exported.exit_code, Some(2));
+        assert_eq!(exported.start_time, "2026-04-08 11:59:59.000");
+        assert_eq!(exported.end_time, "2026-04-08 12:00:00.000");
+    }
+
+    #[test]
+    fn test_command_result_get_duration() {
+        let result = CommandResult {
+            start_time: "2026-04-08 11:59:58.750".to_string(),
+            end_time: "2026-04-08 12:00:00.000".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(result.get_duration(), Some(Duration::from_millis(1250)));
+
+        // Results loaded from old logfiles have no start/end time.
+        assert_eq!(CommandResult::default().get_duration(), None);
+    }
+
+    #[test]
+    fn test_command_result_exit_code_diff() {
+        let command_result1 = CommandResult {
+            status: false,
+            exit_code: Some(1),
+            ..Default::default()
+        };
+        let command_result2 = CommandResult {
+            status: false,
+            exit_code: Some(2),
+            ..Default::default()
+        };
+        assert!(command_result1 != command_result2);
+    }
+
+    fn pty_options() -> ProcessOptions<'static> {
+        ProcessOptions {
+            is_pty: true,
+            ..Default::default()
+        }
     }
 
     #[test]
@@ -235,8 +496,8 @@ mod tests {
             "if [ -t 1 ]; then printf tty; else printf notty; fi".to_string(),
         ];
 
-        let (_, _, stdout, _) = exec_command(&exec_commands, false);
-        assert_eq!(String::from_utf8(stdout).unwrap(), "notty");
+        let result = exec_command(&exec_commands, &ProcessOptions::default());
+        assert_eq!(String::from_utf8(result.stdout).unwrap(), "notty");
     }
 
     #[cfg(unix)]
@@ -248,8 +509,8 @@ mod tests {
             "if [ -t 1 ]; then printf tty; else printf notty; fi".to_string(),
         ];
 
-        let (_, _, stdout, _) = exec_command(&exec_commands, true);
-        assert_eq!(String::from_utf8(stdout).unwrap(), "tty");
+        let result = exec_command(&exec_commands, &pty_options());
+        assert_eq!(String::from_utf8(result.stdout).unwrap(), "tty");
     }
 
     #[cfg(unix)]
@@ -261,8 +522,306 @@ mod tests {
             "if [ -t 0 ]; then printf tty; else printf notty; fi".to_string(),
         ];
 
-        let (_, _, stdout, _) = exec_command(&exec_commands, true);
-        assert_eq!(String::from_utf8(stdout).unwrap(), "tty");
+        let result = exec_command(&exec_commands, &pty_options());
+        assert_eq!(String::from_utf8(result.stdout).unwrap(), "tty");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_command_timeout_kills_process_group() {
+        let exec_commands = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "printf start; sleep 30 & sleep 30".to_string(),
+        ];
+
+        let started = std::time::Instant::now();
+        let result = exec_command(
+            &exec_commands,
+            &ProcessOptions {
+                timeout: Some(Duration::from_millis(200)),
+                ..Default::default()
+            },
+        );
+
+        // The background sleep keeps stdout open, so returning at all means
+        // the whole group was killed.
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(result.timed_out);
+        assert!(!result.status);
+        assert_eq!(result.exit_code, None);
+        assert_eq!(result.signal, Some(15));
+        assert_eq!(String::from_utf8(result.stdout).unwrap(), "start");
+        assert!(result.rusage.is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_command_records_resource_usage() {
+        let exec_commands = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "i=0; while [ $i -lt 200000 ]; do i=$((i+1)); done".to_string(),
+        ];
+
+        let result = exec_command(&exec_commands, &ProcessOptions::default());
+        let rusage = result.rusage.expect("no resource usage recorded");
+        assert!(result.status);
+        assert!(rusage.user_time + rusage.system_time > 0.0);
+        assert!(rusage.max_rss > 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_command_pipes_stdin_with_and_without_pty() {
+        let exec_commands = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "tr a-z A-Z; if [ -t 1 ]; then printf ' tty'; fi".to_string(),
+        ];
+
+        let options = ProcessOptions {
+            stdin: Some(b"select 1"),
+            ..Default::default()
+        };
+        let result = exec_command(&exec_commands, &options);
+        assert_eq!(String::from_utf8(result.stdout).unwrap(), "SELECT 1");
+
+        let options = ProcessOptions {
+            stdin: Some(b"select 1"),
+            ..pty_options()
+        };
+        let result = exec_command(&exec_commands, &options);
+        assert_eq!(String::from_utf8(result.stdout).unwrap(), "SELECT 1 tty");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_command_truncates_each_stream_at_max_output_bytes() {
+        let exec_commands = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "head -c 100000 /dev/zero | tr '\\0' a; printf err >&2".to_string(),
+        ];
+
+        let options = ProcessOptions {
+            max_output_bytes: Some(10),
+            ..Default::default()
+        };
+        let result = exec_command(&exec_commands, &options);
+        assert!(result.status);
+        assert!(result.truncated);
+        assert_eq!(String::from_utf8(result.stdout).unwrap(), "aaaaaaaaaa");
+        assert_eq!(String::from_utf8(result.stderr).unwrap(), "err");
+
+        let options = ProcessOptions {
+            max_output_bytes: Some(100000),
+            ..Default::default()
+        };
+        let result = exec_command(&exec_commands, &options);
+        assert!(!result.truncated);
+        assert_eq!(result.stdout.len(), 100000);
+
+        let options = ProcessOptions {
+            max_output_bytes: Some(10),
+            ..pty_options()
+        };
+        let result = exec_command(&exec_commands, &options);
+        assert!(result.truncated);
+        assert_eq!(result.stdout.len(), 10);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_command_retries_failed_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let counter = dir.path().join("attempts");
+        let retried = Arc::new(std::sync::Mutex::new(vec![]));
+
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut command = ExecuteCommand::new(tx);
+        command.shell_command = "sh -c".to_string();
+        command.command = vec![format!(
+            "echo x >> {0}; [ $(wc -l < {0}) -ge 3 ]",
+            counter.display()
+        )];
+        command.retries = 5;
+        command.retry_delay = Duration::from_millis(10);
+        let on_retry = retried.clone();
+        command.on_retry = Some(Arc::new(move |attempt, attempts| {
+            on_retry.lock().unwrap().push((attempt, attempts));
+        }));
+
+        let result = command.exec().unwrap();
+        assert!(result.status);
+        assert_eq!(result.attempts, 3);
+        assert_eq!(*retried.lock().unwrap(), vec![(2, 6), (3, 6)]);
+
+        // The last failure is recorded once the retries are used up.
+        command.command = vec!["false".to_string()];
+        command.retries = 1;
+        let result = command.exec().unwrap();
+        assert!(!result.status);
+        assert_eq!(result.attempts, 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_command_hashes_the_stdin_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let stdin_file = dir.path().join("query.sql");
+        std::fs::write(&stdin_file, "select 1").unwrap();
+
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut command = ExecuteCommand::new(tx);
+        command.shell_command = "sh -c".to_string();
+        command.command = vec!["cat".to_string()];
+        command.stdin_file = Some(stdin_file.clone());
+
+        let first = command.exec().unwrap();
+        assert_eq!(first.get_output(), "select 1");
+        assert_eq!(first.stdin_hash, content_hash(b"select 1"));
+
+        std::fs::write(&stdin_file, "select 2").unwrap();
+        let second = command.exec().unwrap();
+        assert_eq!(second.get_output(), "select 2");
+        assert_ne!(second.stdin_hash, first.stdin_hash);
+
+        std::fs::remove_file(&stdin_file).unwrap();
+        let missing = command.exec().unwrap();
+        assert!(!missing.status);
+        assert!(missing.get_stderr().contains("failed to read stdin file"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_command_records_exit_code_and_signal() {
+        let exit_commands = vec!["sh".to_string(), "-c".to_string(), "exit 3".to_string()];
+        let result = exec_command(&exit_commands, &ProcessOptions::default());
+        assert!(!result.status);
+        assert_eq!(result.exit_code, Some(3));
+        assert_eq!(result.signal, None);
+
+        let signal_commands = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "kill -SEGV $$".to_string(),
+        ];
+        let result = exec_command(&signal_commands, &ProcessOptions::default());
+        assert!(!result.status);
+        assert_eq!(result.exit_code, None);
+        assert_eq!(result.signal, Some(11));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_command_cancel_kills_running_command() {
+        let exec_commands = vec!["sh".to_string(), "-c".to_string(), "sleep 30".to_string()];
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let flag = cancel.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            flag.store(true, std::sync::atomic::Ordering::Relaxed);
+        });
+
+        let result = exec_command(
+            &exec_commands,
+            &ProcessOptions {
+                cancel: Some(&cancel),
+                ..Default::default()
+            },
+        );
+
+        assert!(result.cancelled);
+        assert!(!result.timed_out);
+        assert!(!result.status);
+    }
+
+    #[test]
+    fn test_exec_command_streams_chunks_before_returning() {
+        let exec_commands = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "printf out; printf err >&2".to_string(),
+        ];
+        let chunks = Arc::new(std::sync::Mutex::new(vec![]));
+
+        let received = chunks.clone();
+        let on_output: OutputCallback = Arc::new(move |mode, chunk: &[u8]| {
+            let stream = match mode {
+                common::OutputMode::Stderr => "stderr",
+                _ => "stdout",
+            };
+            received
+                .lock()
+                .unwrap()
+                .push(format!("{stream}:{}", String::from_utf8_lossy(chunk)));
+        });
+        let options = ProcessOptions {
+            on_output: Some(on_output),
+            ..Default::default()
+        };
+
+        let result = exec_command(&exec_commands, &options);
+
+        let mut chunks = chunks.lock().unwrap().clone();
+        chunks.sort();
+        assert_eq!(chunks, vec!["stderr:err", "stdout:out"]);
+        assert_eq!(String::from_utf8(result.output).unwrap(), "outerr");
+    }
+
+    #[test]
+    fn test_exec_command_finishes_before_timeout() {
+        let exec_commands = vec!["sh".to_string(), "-c".to_string(), "printf ok".to_string()];
+
+        let result = exec_command(
+            &exec_commands,
+            &ProcessOptions {
+                timeout: Some(Duration::from_secs(10)),
+                ..Default::default()
+            },
+        );
+
+        assert!(!result.timed_out);
+        assert!(result.status);
+        assert_eq!(String::from_utf8(result.stdout).unwrap(), "ok");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_command_pty_uses_given_size() {
+        let exec_commands = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "stty size; printf '%s %s' \"$COLUMNS\" \"$LINES\"".to_string(),
+        ];
+        let options = ProcessOptions {
+            is_pty: true,
+            pty_size: PtySize {
+                cols: 200,
+                rows: 50,
+            },
+            ..Default::default()
+        };
+
+        let result = exec_command(&exec_commands, &options);
+        let stdout = String::from_utf8(result.stdout).unwrap();
+        assert_eq!(stdout.replace("\r\n", "\n"), "50 200\n200 50");
+    }
+
+    #[test]
+    fn test_pty_size_from_str() {
+        assert_eq!(
+            "200x50".parse::<PtySize>(),
+            Ok(PtySize {
+                cols: 200,
+                rows: 50
+            })
+        );
+        assert!("200".parse::<PtySize>().is_err());
+        assert!("0x50".parse::<PtySize>().is_err());
+        assert!("wide x tall".parse::<PtySize>().is_err());
     }
 
     #[test]
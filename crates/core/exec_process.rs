@@ -8,11 +8,28 @@ use std::io::prelude::*;
 use std::io::BufReader;
 #[cfg(unix)]
 use std::os::fd::OwnedFd;
-use std::process::{Command, Stdio};
+#[cfg(unix)]
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(unix)]
+use nix::sys::signal::{killpg, Signal};
+#[cfg(unix)]
+use nix::unistd::Pid;
 
 #[cfg(unix)]
 use super::pty::create_raw_pty;
+use super::pty::PtySize;
+use super::result::ResourceUsage;
+use crate::common::OutputMode;
+
+/// Receives every chunk read from the command's stdout or stderr while it runs.
+pub type OutputCallback = Arc<dyn Fn(OutputMode, &[u8]) + Send + Sync>;
 
 pub(super) fn create_exec_cmd_args(
     is_exec: bool,
@@ -69,13 +86,60 @@ pub(super) fn create_exec_cmd_args(
     Ok(exec_commands)
 }
 
-pub(super) fn exec_command(
-    exec_commands: &[String],
-    is_pty: bool,
-) -> (bool, Vec<u8>, Vec<u8>, Vec<u8>) {
+// How often a running child is polled while a timeout or cancel is armed.
+const TIMEOUT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+// Time given to the process group between SIGTERM and SIGKILL.
+const TIMEOUT_KILL_GRACE: Duration = Duration::from_millis(500);
+
+/// How a single run of the command is spawned and supervised.
+#[derive(Default)]
+pub(super) struct ProcessOptions<'a> {
+    pub is_pty: bool,
+    pub pty_size: PtySize,
+    pub timeout: Option<Duration>,
+    pub cancel: Option<&'a Arc<AtomicBool>>,
+    pub on_output: Option<OutputCallback>,
+    pub cwd: Option<&'a Path>,
+    pub env: &'a [(String, String)],
+    pub stdin: Option<&'a [u8]>,
+    pub max_output_bytes: Option<usize>,
+}
+
+///
+#[derive(Debug, Default)]
+pub(super) struct ProcessOutput {
+    pub status: bool,
+    pub timed_out: bool,
+    pub cancelled: bool,
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    pub rusage: Option<ResourceUsage>,
+    pub truncated: bool,
+    pub output: Vec<u8>,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+pub(super) fn exec_command(exec_commands: &[String], options: &ProcessOptions) -> ProcessOutput {
+    let is_pty = options.is_pty;
+    let timeout = options.timeout;
+    let cancel = options.cancel;
+
     let length = exec_commands.len();
     let mut command = Command::new(&exec_commands[0]);
     command.args(&exec_commands[1..length]);
+    command.envs(options.env.iter().map(|(key, value)| (key, value)));
+    if let Some(cwd) = options.cwd {
+        command.current_dir(cwd);
+    }
+
+    // Put the child in its own process group, so that a timeout can take
+    // down everything it spawned and not only the shell.
+    #[cfg(unix)]
+    if timeout.is_some() || cancel.is_some() {
+        command.process_group(0);
+    }
 
     #[cfg(unix)]
     let mut stdin_master: Option<OwnedFd> = None;
@@ -88,17 +152,19 @@ pub(super) fn exec_command(
     #[cfg(unix)]
     {
         if is_pty {
-            let stdin_pty = create_raw_pty();
-            let stdout_pty = create_raw_pty();
-            let stderr_pty = create_raw_pty();
+            let stdin_pty = create_raw_pty(options.pty_size);
+            let stdout_pty = create_raw_pty(options.pty_size);
+            let stderr_pty = create_raw_pty(options.pty_size);
 
             let (stdin_pty, stdout_pty, stderr_pty) = match (stdin_pty, stdout_pty, stderr_pty) {
                 (Ok(stdin_pty), Ok(stdout_pty), Ok(stderr_pty)) => {
                     (stdin_pty, stdout_pty, stderr_pty)
                 }
                 (Err(err), _, _) | (_, Err(err), _) | (_, _, Err(err)) => {
-                    let error_msg = err.to_string().into_bytes();
-                    return (false, Vec::new(), Vec::new(), error_msg);
+                    return ProcessOutput {
+                        stderr: err.to_string().into_bytes(),
+                        ..Default::default()
+                    };
                 }
             };
 
@@ -107,6 +173,8 @@ pub(super) fn exec_command(
             stderr_reader = ReaderHandle::Fd(stderr_pty.master);
 
             command
+                .env("COLUMNS", options.pty_size.cols.to_string())
+                .env("LINES", options.pty_size.rows.to_string())
                 .stdin(Stdio::from(stdin_pty.slave))
                 .stdout(Stdio::from(stdout_pty.slave))
                 .stderr(Stdio::from(stderr_pty.slave));
@@ -124,21 +192,42 @@ pub(super) fn exec_command(
         stderr_reader = ReaderHandle::Pipe;
     }
 
+    // Given stdin is piped in every mode. With a pty only stdout and stderr
+    // need to be terminals, and a pipe gives the command a clean EOF.
+    if options.stdin.is_some() {
+        command.stdin(Stdio::piped());
+    }
+
     let child_result = command.spawn();
     drop(command);
     let _ = stdin_master;
     let mut vec_output = Vec::new();
     let mut vec_stdout = Vec::new();
     let mut vec_stderr = Vec::new();
+    let mut timed_out = false;
+    let mut cancelled = false;
+    let mut exit_status = None;
+    let mut rusage = None;
+    let mut truncated = false;
 
     let status = match child_result {
         Ok(mut child) => {
+            // Not joined: a command that never reads its stdin must not hold up the run.
+            if let (Some(stdin), Some(mut child_stdin)) = (options.stdin, child.stdin.take()) {
+                let stdin = stdin.to_vec();
+                thread::spawn(move || child_stdin.write_all(&stdin));
+            }
+
             let stdout_thread = match stdout_reader {
                 #[cfg(unix)]
-                ReaderHandle::Fd(fd) => thread::spawn(move || read_from_fd(fd, "stdout")),
+                ReaderHandle::Fd(fd) => {
+                    let reader = ChunkReader::new(OutputMode::Stdout, options);
+                    thread::spawn(move || reader.read_from_fd(fd))
+                }
                 ReaderHandle::Pipe => match child.stdout.take() {
                     Some(child_stdout) => {
-                        thread::spawn(move || read_from_pipe(child_stdout, "stdout"))
+                        let reader = ChunkReader::new(OutputMode::Stdout, options);
+                        thread::spawn(move || reader.read_from_pipe(child_stdout))
                     }
                     None => thread::spawn(|| {
                         Err("stdout pipe was not available on spawned process".to_string())
@@ -147,10 +236,14 @@ pub(super) fn exec_command(
             };
             let stderr_thread = match stderr_reader {
                 #[cfg(unix)]
-                ReaderHandle::Fd(fd) => thread::spawn(move || read_from_fd(fd, "stderr")),
+                ReaderHandle::Fd(fd) => {
+                    let reader = ChunkReader::new(OutputMode::Stderr, options);
+                    thread::spawn(move || reader.read_from_fd(fd))
+                }
                 ReaderHandle::Pipe => match child.stderr.take() {
                     Some(child_stderr) => {
-                        thread::spawn(move || read_from_pipe(child_stderr, "stderr"))
+                        let reader = ChunkReader::new(OutputMode::Stderr, options);
+                        thread::spawn(move || reader.read_from_pipe(child_stderr))
                     }
                     None => thread::spawn(|| {
                         Err("stderr pipe was not available on spawned process".to_string())
@@ -158,27 +251,31 @@ pub(super) fn exec_command(
                 },
             };
 
-            let status = if is_pty {
-                child.wait().map(|status| status.success()).unwrap_or(false)
-            } else {
-                false
-            };
-            vec_stdout = stdout_thread
+            // Wait before joining the readers: the pty masters only reach EOF
+            // once the child is gone, and a timed out child has to be killed
+            // before its pipes are closed.
+            let (reaped, wait_end) = wait_child(&mut child, timeout, cancel);
+            timed_out = wait_end == WaitEnd::TimedOut;
+            cancelled = wait_end == WaitEnd::Cancelled;
+            let wait_status = reaped.map(|(status, _)| status);
+            exit_status = wait_status;
+            rusage = reaped.and_then(|(_, rusage)| rusage);
+
+            let stdout = stdout_thread
                 .join()
                 .unwrap_or_else(|_| Err("Failed to join stdout thread".to_string()))
-                .unwrap_or_else(|err| format!("{err}\n").into_bytes());
-            vec_stderr = stderr_thread
+                .unwrap_or_else(Captured::error);
+            let stderr = stderr_thread
                 .join()
                 .unwrap_or_else(|_| Err("Failed to join stderr thread".to_string()))
-                .unwrap_or_else(|err| format!("{err}\n").into_bytes());
+                .unwrap_or_else(Captured::error);
+            truncated = stdout.truncated || stderr.truncated;
+            vec_stdout = stdout.data;
+            vec_stderr = stderr.data;
             vec_output = vec_stdout.clone();
             vec_output.extend_from_slice(&vec_stderr);
 
-            if is_pty {
-                status
-            } else {
-                child.wait().map(|status| status.success()).unwrap_or(false)
-            }
+            wait_end == WaitEnd::Exited && wait_status.is_some_and(|status| status.success())
         }
         Err(err) => {
             let error_msg = err.to_string();
@@ -192,7 +289,159 @@ pub(super) fn exec_command(
         }
     };
 
-    (status, vec_output, vec_stdout, vec_stderr)
+    ProcessOutput {
+        status,
+        timed_out,
+        cancelled,
+        exit_code: exit_status.and_then(|status| status.code()),
+        signal: exit_status.and_then(exit_signal),
+        rusage,
+        truncated,
+        output: vec_output,
+        stdout: vec_stdout,
+        stderr: vec_stderr,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WaitEnd {
+    Exited,
+    TimedOut,
+    Cancelled,
+}
+
+/// Exit status of a reaped child, with its resource usage where the platform
+/// reports it.
+type Reaped = (ExitStatus, Option<ResourceUsage>);
+
+/// Wait for the child to exit, killing it when the timeout expires or the
+/// cancel flag is raised.
+fn wait_child(
+    child: &mut Child,
+    timeout: Option<Duration>,
+    cancel: Option<&Arc<AtomicBool>>,
+) -> (Option<Reaped>, WaitEnd) {
+    if timeout.is_none() && cancel.is_none() {
+        return (reap_child(child).ok(), WaitEnd::Exited);
+    }
+
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let wait_end = loop {
+        match try_reap_child(child) {
+            Ok(Some(reaped)) => return (Some(reaped), WaitEnd::Exited),
+            Ok(None) => {}
+            Err(_) => return (None, WaitEnd::Exited),
+        }
+
+        if cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed)) {
+            break WaitEnd::Cancelled;
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break WaitEnd::TimedOut;
+        }
+        thread::sleep(TIMEOUT_POLL_INTERVAL);
+    };
+
+    (terminate_child(child), wait_end)
+}
+
+#[cfg(unix)]
+fn exit_signal(status: ExitStatus) -> Option<i32> {
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: ExitStatus) -> Option<i32> {
+    None
+}
+
+#[cfg(unix)]
+fn terminate_child(child: &mut Child) -> Option<Reaped> {
+    let pgid = Pid::from_raw(child.id() as i32);
+    let _ = killpg(pgid, Signal::SIGTERM);
+
+    let mut reaped = None;
+    let deadline = Instant::now() + TIMEOUT_KILL_GRACE;
+    while Instant::now() < deadline {
+        if let Ok(Some(status)) = try_reap_child(child) {
+            reaped = Some(status);
+            break;
+        }
+        thread::sleep(TIMEOUT_POLL_INTERVAL);
+    }
+
+    // The group leader may have exited while its children ignore SIGTERM.
+    let _ = killpg(pgid, Signal::SIGKILL);
+    reaped.or_else(|| reap_child(child).ok())
+}
+
+#[cfg(not(unix))]
+fn terminate_child(child: &mut Child) -> Option<Reaped> {
+    let _ = child.kill();
+    reap_child(child).ok()
+}
+
+// The child is reaped with wait4(2) instead of `Child::wait`, which throws
+// away the rusage. Once reaped, `Child::wait` must not be called any more.
+#[cfg(unix)]
+fn wait4_child(child: &Child, options: libc::c_int) -> std::io::Result<Option<Reaped>> {
+    let pid = child.id() as libc::pid_t;
+    let mut status = 0;
+    // SAFETY: rusage is plain old data, and both pointers outlive the call.
+    let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        match unsafe { libc::wait4(pid, &mut status, options, &mut rusage) } {
+            0 => return Ok(None),
+            -1 => {
+                let err = std::io::Error::last_os_error();
+                if err.kind() != std::io::ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+            _ => {
+                let status = ExitStatus::from_raw(status);
+                return Ok(Some((status, Some(resource_usage(&rusage)))));
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn resource_usage(rusage: &libc::rusage) -> ResourceUsage {
+    let seconds = |time: libc::timeval| time.tv_sec as f64 + time.tv_usec as f64 / 1_000_000.0;
+
+    // Linux reports the peak RSS in KiB, macOS in bytes.
+    let max_rss = rusage.ru_maxrss.max(0) as u64;
+    #[cfg(target_os = "macos")]
+    let max_rss = max_rss / 1024;
+
+    ResourceUsage {
+        user_time: seconds(rusage.ru_utime),
+        system_time: seconds(rusage.ru_stime),
+        max_rss,
+    }
+}
+
+#[cfg(unix)]
+fn reap_child(child: &mut Child) -> std::io::Result<Reaped> {
+    wait4_child(child, 0)?.ok_or_else(|| std::io::Error::other("wait4 returned without a status"))
+}
+
+#[cfg(unix)]
+fn try_reap_child(child: &mut Child) -> std::io::Result<Option<Reaped>> {
+    wait4_child(child, libc::WNOHANG)
+}
+
+#[cfg(not(unix))]
+fn reap_child(child: &mut Child) -> std::io::Result<Reaped> {
+    child.wait().map(|status| (status, None))
+}
+
+#[cfg(not(unix))]
+fn try_reap_child(child: &mut Child) -> std::io::Result<Option<Reaped>> {
+    child
+        .try_wait()
+        .map(|status| status.map(|status| (status, None)))
 }
 
 #[cfg(unix)]
@@ -206,36 +455,118 @@ enum ReaderHandle {
     Pipe,
 }
 
-#[cfg(unix)]
-fn read_from_fd(fd: OwnedFd, label: &str) -> Result<Vec<u8>, String> {
-    use std::io::ErrorKind;
+/// One output stream of the command, cut at `--max-output-bytes`.
+#[derive(Default)]
+struct Captured {
+    data: Vec<u8>,
+    truncated: bool,
+}
 
-    let mut file = File::from(fd);
-    let mut buf = Vec::new();
-    let mut chunk = [0_u8; 8192];
+impl Captured {
+    fn error(err: String) -> Self {
+        Self {
+            data: format!("{err}\n").into_bytes(),
+            truncated: false,
+        }
+    }
+}
 
-    loop {
-        match file.read(&mut chunk) {
-            Ok(0) => break,
-            Ok(size) => buf.extend_from_slice(&chunk[..size]),
-            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
-            Err(err) if err.kind() == ErrorKind::UnexpectedEof || err.raw_os_error() == Some(5) => {
-                break;
+/// Reads one output stream of the command until EOF. Once `limit` bytes are
+/// stored the rest is still read, so the command never blocks on a full pipe,
+/// but thrown away.
+struct ChunkReader {
+    mode: OutputMode,
+    on_output: Option<OutputCallback>,
+    limit: Option<usize>,
+}
+
+impl ChunkReader {
+    fn new(mode: OutputMode, options: &ProcessOptions) -> Self {
+        Self {
+            mode,
+            on_output: options.on_output.clone(),
+            limit: options.max_output_bytes,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self.mode {
+            OutputMode::Stderr => "stderr",
+            _ => "stdout",
+        }
+    }
+
+    fn push(&self, captured: &mut Captured, chunk: &[u8]) {
+        let chunk = match self.limit {
+            Some(limit) if captured.data.len() + chunk.len() > limit => {
+                captured.truncated = true;
+                &chunk[..limit.saturating_sub(captured.data.len())]
             }
-            Err(err) => return Err(format!("Failed to read {label}: {err}")),
+            _ => chunk,
+        };
+        if chunk.is_empty() {
+            return;
+        }
+
+        if let Some(on_output) = &self.on_output {
+            on_output(self.mode, chunk);
         }
+        captured.data.extend_from_slice(chunk);
     }
 
-    Ok(buf)
-}
+    #[cfg(unix)]
+    fn read_from_fd(self, fd: OwnedFd) -> Result<Captured, String> {
+        use std::io::ErrorKind;
+
+        let mut file = File::from(fd);
+        let mut captured = Captured::default();
+        let mut chunk = [0_u8; 8192];
+
+        loop {
+            match file.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(size) => self.push(&mut captured, &chunk[..size]),
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err)
+                    if err.kind() == ErrorKind::UnexpectedEof || err.raw_os_error() == Some(5) =>
+                {
+                    break;
+                }
+                Err(err) => return Err(format!("Failed to read {}: {err}", self.label())),
+            }
+        }
 
-fn read_from_pipe<R: Read>(reader: R, label: &str) -> Result<Vec<u8>, String> {
-    let mut reader = BufReader::new(reader);
-    let mut buf = Vec::new();
-    reader
-        .read_to_end(&mut buf)
-        .map_err(|err| format!("Failed to read {label}: {err}"))?;
-    Ok(buf)
+        Ok(captured)
+    }
+
+    fn read_from_pipe<R: Read>(self, reader: R) -> Result<Captured, String> {
+        use std::io::ErrorKind;
+
+        // Without a callback or a limit there is nothing to do per chunk, so read in one go.
+        if self.on_output.is_none() && self.limit.is_none() {
+            let mut reader = BufReader::new(reader);
+            let mut captured = Captured::default();
+            reader
+                .read_to_end(&mut captured.data)
+                .map_err(|err| format!("Failed to read {}: {err}", self.label()))?;
+            return Ok(captured);
+        }
+
+        let mut reader = reader;
+        let mut captured = Captured::default();
+        let mut chunk = [0_u8; 8192];
+
+        loop {
+            match reader.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(size) => self.push(&mut captured, &chunk[..size]),
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(format!("Failed to read {}: {err}", self.label())),
+            }
+        }
+
+        Ok(captured)
+    }
 }
 
 fn should_append_command_to_previous_arg(exec_commands: &[String]) -> bool {
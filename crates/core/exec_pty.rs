@@ -6,12 +6,44 @@
 use nix::pty::{openpty, OpenptyResult, Winsize};
 #[cfg(unix)]
 use nix::sys::termios::{cfmakeraw, tcgetattr, tcsetattr, SetArg};
+use std::str::FromStr;
+
+/// Window size of the pty used by `--use-pty`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PtySize {
+    pub cols: u16,
+    pub rows: u16,
+}
+
+impl Default for PtySize {
+    fn default() -> Self {
+        Self { cols: 80, rows: 24 }
+    }
+}
+
+impl FromStr for PtySize {
+    type Err = String;
+
+    /// Parse `COLSxROWS`, e.g. `200x50`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid size '{value}'. expected COLSxROWS, e.g. 200x50");
+
+        let (cols, rows) = value.split_once(['x', 'X']).ok_or_else(error)?;
+        let cols: u16 = cols.trim().parse().map_err(|_| error())?;
+        let rows: u16 = rows.trim().parse().map_err(|_| error())?;
+        if cols == 0 || rows == 0 {
+            return Err(error());
+        }
+
+        Ok(Self { cols, rows })
+    }
+}
 
 #[cfg(unix)]
-pub(super) fn create_raw_pty() -> Result<OpenptyResult, nix::Error> {
+pub(super) fn create_raw_pty(size: PtySize) -> Result<OpenptyResult, nix::Error> {
     let winsize = Winsize {
-        ws_row: 24,
-        ws_col: 80,
+        ws_row: size.rows,
+        ws_col: size.cols,
         ws_xpixel: 0,
         ws_ypixel: 0,
     };
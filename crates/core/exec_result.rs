@@ -6,14 +6,59 @@ use chardetng::EncodingDetector;
 use flate2::{read::GzDecoder, write::GzEncoder};
 use serde::{Deserialize, Serialize};
 use std::io::{Read, Write};
+use std::time::Duration;
 
-use crate::common::OutputMode;
+use crate::common::{self, OutputMode};
+
+/// CPU time and peak memory of a finished run, as reported by `wait4(2)`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct ResourceUsage {
+    /// user CPU time in seconds.
+    pub user_time: f64,
+    /// system CPU time in seconds.
+    pub system_time: f64,
+    /// peak resident set size in KiB.
+    pub max_rss: u64,
+}
+
+impl ResourceUsage {
+    /// user and system CPU time together.
+    pub fn cpu_time(&self) -> Duration {
+        Duration::from_secs_f64(self.user_time + self.system_time)
+    }
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct CommandResultData {
     pub timestamp: String,
     pub command: String,
     pub status: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signal: Option<i32>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub start_time: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub end_time: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub trigger: String,
+    /// an unchanged run logged by `--log-heartbeat`, without output
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub heartbeat: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rusage: Option<ResourceUsage>,
+    /// CRC32 of the `--stdin-file` content the command was fed
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub stdin_hash: String,
+    /// output cut at `--max-output-bytes`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// runs of the command it took to get this result, with `--retries`
+    #[serde(default = "first_attempt", skip_serializing_if = "is_first_attempt")]
+    pub attempts: u32,
     pub output: String,
     pub stdout: String,
     pub stderr: String,
@@ -29,6 +74,17 @@ impl CommandResultData {
             timestamp: self.timestamp.clone(),
             command: self.command.clone(),
             status: self.status,
+            timed_out: self.timed_out,
+            exit_code: self.exit_code,
+            signal: self.signal,
+            start_time: self.start_time.clone(),
+            end_time: self.end_time.clone(),
+            trigger: self.trigger.clone(),
+            heartbeat: self.heartbeat,
+            rusage: self.rusage,
+            stdin_hash: self.stdin_hash.clone(),
+            truncated: self.truncated,
+            attempts: self.attempts,
             is_compress,
             output: vec![],
             stdout: vec![],
@@ -45,6 +101,32 @@ pub struct CommandResult {
     pub timestamp: String,
     pub command: String,
     pub status: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signal: Option<i32>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub start_time: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub end_time: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub trigger: String,
+    /// an unchanged run logged by `--log-heartbeat`, without output
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub heartbeat: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rusage: Option<ResourceUsage>,
+    /// CRC32 of the `--stdin-file` content the command was fed
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub stdin_hash: String,
+    /// output cut at `--max-output-bytes`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// runs of the command it took to get this result, with `--retries`
+    #[serde(default = "first_attempt", skip_serializing_if = "is_first_attempt")]
+    pub attempts: u32,
     pub is_compress: bool,
     pub output: Vec<u8>,
     pub stdout: Vec<u8>,
@@ -57,6 +139,17 @@ impl Default for CommandResult {
             timestamp: String::default(),
             command: String::default(),
             status: true,
+            timed_out: false,
+            exit_code: None,
+            signal: None,
+            start_time: String::default(),
+            end_time: String::default(),
+            trigger: String::default(),
+            heartbeat: false,
+            rusage: None,
+            stdin_hash: String::default(),
+            truncated: false,
+            attempts: 1,
             is_compress: false,
             output: vec![],
             stdout: vec![],
@@ -69,6 +162,9 @@ impl PartialEq for CommandResult {
     fn eq(&self, other: &Self) -> bool {
         self.command == other.command
             && self.status == other.status
+            && self.timed_out == other.timed_out
+            && self.exit_code == other.exit_code
+            && self.signal == other.signal
             && self.output == other.output
             && self.stdout == other.stdout
             && self.stderr == other.stderr
@@ -142,11 +238,43 @@ impl CommandResult {
         self.get_data(OutputMode::Stderr)
     }
 
+    /// Wall-clock time the command took, if start and end were recorded.
+    pub fn get_duration(&self) -> Option<Duration> {
+        common::duration_between(&self.start_time, &self.end_time)
+    }
+
+    ///
+    pub fn get_exit_text(&self) -> String {
+        common::exit_status_text(self.timed_out, self.exit_code, self.signal)
+    }
+
+    /// Copy of an unchanged run for the logfile, without the output.
+    pub fn to_heartbeat(&self) -> Self {
+        CommandResult {
+            heartbeat: true,
+            output: vec![],
+            stdout: vec![],
+            stderr: vec![],
+            ..self.clone()
+        }
+    }
+
     pub fn export_data(&self) -> CommandResultData {
         CommandResultData {
             timestamp: self.timestamp.clone(),
             command: self.command.clone(),
             status: self.status,
+            timed_out: self.timed_out,
+            exit_code: self.exit_code,
+            signal: self.signal,
+            start_time: self.start_time.clone(),
+            end_time: self.end_time.clone(),
+            trigger: self.trigger.clone(),
+            heartbeat: self.heartbeat,
+            rusage: self.rusage,
+            stdin_hash: self.stdin_hash.clone(),
+            truncated: self.truncated,
+            attempts: self.attempts,
             output: self.get_output(),
             stdout: self.get_stdout(),
             stderr: self.get_stderr(),
@@ -154,6 +282,14 @@ impl CommandResult {
     }
 }
 
+fn first_attempt() -> u32 {
+    1
+}
+
+fn is_first_attempt(attempts: &u32) -> bool {
+    *attempts <= 1
+}
+
 pub(super) fn decode_bytes(data: &[u8]) -> String {
     if data.is_empty() {
         return String::new();
@@ -0,0 +1,218 @@
+// Copyright (c) 2026 Blacknon. All rights reserved.
+// Use of this source code is governed by an MIT license
+// that can be found in the LICENSE file.
+
+// module
+use crossbeam_channel::Sender;
+use std::fs::{self, File, Metadata};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+// local module
+use crate::event::AppEvent;
+use crate::exec::CommandResultData;
+
+/// How often the logfile is checked for new records.
+pub const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Tails a logfile written by another hwatch, and sends each new record
+/// through `AppEvent::OutputUpdate` like the output of a run.
+pub struct LogFollower {
+    path: PathBuf,
+    is_compress: bool,
+    tx: Sender<AppEvent>,
+}
+
+/// Where reading of the followed file stopped.
+#[derive(Default)]
+struct Tail {
+    file: Option<File>,
+    id: Option<FileId>,
+    offset: u64,
+
+    /// a record whose line has not been completely written yet
+    partial: Vec<u8>,
+}
+
+impl LogFollower {
+    pub fn new(path: PathBuf, tx: Sender<AppEvent>) -> Self {
+        Self {
+            path,
+            is_compress: false,
+            tx,
+        }
+    }
+
+    pub fn set_compress(mut self, is_compress: bool) -> Self {
+        self.is_compress = is_compress;
+        self
+    }
+
+    /// Read the records already in the file, then keep following it until
+    /// the app goes away.
+    pub fn spawn(self) -> JoinHandle<()> {
+        thread::spawn(move || {
+            let mut tail = Tail::default();
+            while self.poll(&mut tail) {
+                thread::sleep(FOLLOW_POLL_INTERVAL);
+            }
+        })
+    }
+
+    /// Returns false once the records can no longer be sent.
+    fn poll(&self, tail: &mut Tail) -> bool {
+        let current = fs::metadata(&self.path).ok();
+
+        // Rotated: the path is another file now. What was appended to the old
+        // one before the rename is read first.
+        if tail.file.is_some() && current.as_ref().map(file_id) != tail.id {
+            if !self.read_new(tail) {
+                return false;
+            }
+            *tail = Tail::default();
+        }
+
+        if tail.file.is_none() {
+            let Ok(file) = File::open(&self.path) else {
+                return true;
+            };
+            tail.id = file.metadata().ok().as_ref().map(file_id);
+            tail.file = Some(file);
+        }
+
+        // Truncated in place: start over from the top.
+        if current.is_some_and(|metadata| metadata.len() < tail.offset) {
+            if let Some(file) = tail.file.as_mut() {
+                let _ = file.seek(SeekFrom::Start(0));
+            }
+            tail.offset = 0;
+            tail.partial.clear();
+        }
+
+        self.read_new(tail)
+    }
+
+    fn read_new(&self, tail: &mut Tail) -> bool {
+        let Some(file) = tail.file.as_mut() else {
+            return true;
+        };
+        let Ok(size) = file.read_to_end(&mut tail.partial) else {
+            return true;
+        };
+        tail.offset += size as u64;
+
+        let Some(end) = tail.partial.iter().rposition(|&byte| byte == b'\n') else {
+            return true;
+        };
+        let lines: Vec<u8> = tail.partial.drain(..=end).collect();
+
+        for line in lines.split(|&byte| byte == b'\n') {
+            // A line that is not a record (e.g. cut by a crash) is skipped.
+            let Ok(data) = serde_json::from_slice::<CommandResultData>(line) else {
+                continue;
+            };
+            let result = data.generate_result(self.is_compress);
+            if self
+                .tx
+                .send(AppEvent::OutputUpdate(Box::new(result)))
+                .is_err()
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(unix)]
+type FileId = (u64, u64);
+
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> FileId {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.dev(), metadata.ino())
+}
+
+// Without inode numbers a rotation is only noticed as a truncation.
+#[cfg(not(unix))]
+type FileId = ();
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> FileId {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::logging_result;
+    use crate::exec::CommandResult;
+    use crossbeam_channel::{unbounded, Receiver};
+
+    fn log(path: &std::path::Path, output: &str) {
+        let result = CommandResult::default().set_output(output.as_bytes().to_vec());
+        logging_result(path.to_str().unwrap(), &result).unwrap();
+    }
+
+    fn followed(rx: &Receiver<AppEvent>) -> Vec<String> {
+        rx.try_iter()
+            .map(|event| match event {
+                AppEvent::OutputUpdate(result) => result.get_output(),
+                _ => panic!("unexpected event"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn new_records_are_followed_across_truncation_and_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("probe.jsonl");
+        log(&path, "first");
+
+        let (tx, rx) = unbounded();
+        let follower = LogFollower::new(path.clone(), tx);
+        let mut tail = Tail::default();
+        assert!(follower.poll(&mut tail));
+        assert_eq!(followed(&rx), vec!["first"]);
+
+        log(&path, "appended");
+        assert!(follower.poll(&mut tail));
+        assert_eq!(followed(&rx), vec!["appended"]);
+
+        fs::write(&path, "").unwrap();
+        log(&path, "new");
+        assert!(follower.poll(&mut tail));
+        assert_eq!(followed(&rx), vec!["new"]);
+
+        // The last record written to the old file is not lost.
+        log(&path, "before rotation");
+        fs::rename(&path, dir.path().join("probe.jsonl.1")).unwrap();
+        log(&path, "after rotation");
+        assert!(follower.poll(&mut tail));
+        assert_eq!(followed(&rx), vec!["before rotation", "after rotation"]);
+    }
+
+    #[test]
+    fn a_partly_written_record_waits_for_its_newline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("probe.jsonl");
+        let record = serde_json::to_string(
+            &CommandResult::default()
+                .set_output(b"whole".to_vec())
+                .export_data(),
+        )
+        .unwrap();
+        let (head, rest) = record.split_at(record.len() / 2);
+        fs::write(&path, head).unwrap();
+
+        let (tx, rx) = unbounded();
+        let follower = LogFollower::new(path.clone(), tx);
+        let mut tail = Tail::default();
+        assert!(follower.poll(&mut tail));
+        assert!(followed(&rx).is_empty());
+
+        fs::write(&path, format!("{head}{rest}\n")).unwrap();
+        assert!(follower.poll(&mut tail));
+        assert_eq!(followed(&rx), vec!["whole"]);
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright (c) 2026 Blacknon. All rights reserved.
+// Use of this source code is governed by an MIT license
+// that can be found in the LICENSE file.
+
+// module
+use crossbeam_channel::Receiver;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "linux")]
+#[path = "fswatch_inotify.rs"]
+mod inotify;
+
+#[cfg(target_os = "linux")]
+use self::inotify::PathWatcher;
+
+/// Changes that arrive within this window of each other start a single run.
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// A path that never stops changing still starts a run this often.
+pub const WATCH_DEBOUNCE_MAX: Duration = Duration::from_secs(2);
+
+/// Start watching the globs. Changed paths are sent on the returned channel.
+#[cfg(target_os = "linux")]
+pub fn watch_paths(globs: &[String]) -> Result<Receiver<PathBuf>, String> {
+    Ok(PathWatcher::new(globs)?.spawn())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn watch_paths(_globs: &[String]) -> Result<Receiver<PathBuf>, String> {
+    Err("is only supported on Linux.".to_string())
+}
+
+/// Swallow the changes that follow `first` until none arrive for
+/// `WATCH_DEBOUNCE`, so a burst of writes starts a single run. Gives up
+/// after `WATCH_DEBOUNCE_MAX`.
+pub fn debounce(rx: &Receiver<PathBuf>, first: PathBuf) -> PathBuf {
+    let deadline = Instant::now() + WATCH_DEBOUNCE_MAX;
+    while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+        if rx.recv_timeout(WATCH_DEBOUNCE.min(left)).is_err() {
+            break;
+        }
+    }
+    first
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel::unbounded;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn debounce_swallows_a_burst() {
+        let (tx, rx) = unbounded();
+        tx.send(PathBuf::from("b")).unwrap();
+        tx.send(PathBuf::from("c")).unwrap();
+
+        let start = Instant::now();
+        assert_eq!(debounce(&rx, PathBuf::from("a")), PathBuf::from("a"));
+        assert!(start.elapsed() >= WATCH_DEBOUNCE);
+        assert!(rx.is_empty());
+    }
+
+    #[test]
+    fn debounce_returns_while_changes_keep_coming() {
+        let (tx, rx) = unbounded();
+        let stop = Arc::new(AtomicBool::new(false));
+        let sender = {
+            let stop = stop.clone();
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let _ = tx.send(PathBuf::from("log"));
+                    thread::sleep(WATCH_DEBOUNCE / 4);
+                }
+            })
+        };
+
+        let start = Instant::now();
+        assert_eq!(debounce(&rx, PathBuf::from("a")), PathBuf::from("a"));
+        assert!(start.elapsed() < WATCH_DEBOUNCE_MAX + WATCH_DEBOUNCE);
+
+        stop.store(true, Ordering::Relaxed);
+        sender.join().unwrap();
+    }
+}
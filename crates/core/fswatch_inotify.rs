@@ -0,0 +1,273 @@
+// Copyright (c) 2026 Blacknon. All rights reserved.
+// Use of this source code is governed by an MIT license
+// that can be found in the LICENSE file.
+
+// module
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use glob::Pattern;
+use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify, WatchDescriptor};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::thread;
+
+const WATCH_FLAGS: AddWatchFlags = AddWatchFlags::IN_CLOSE_WRITE
+    .union(AddWatchFlags::IN_MODIFY)
+    .union(AddWatchFlags::IN_CREATE)
+    .union(AddWatchFlags::IN_DELETE)
+    .union(AddWatchFlags::IN_MOVED_FROM)
+    .union(AddWatchFlags::IN_MOVED_TO);
+
+/// One `--watch-path` glob, split into the directory that has to be watched
+/// and the pattern that changed paths are matched against.
+#[derive(Debug)]
+struct WatchPattern {
+    pattern: Pattern,
+
+    /// leading part of the glob without wildcards
+    base: PathBuf,
+
+    /// how many directory levels below `base` the glob can reach, `None` for `**`
+    depth: Option<usize>,
+}
+
+impl WatchPattern {
+    fn new(glob: &str) -> Result<Self, String> {
+        // A plain directory means everything below it.
+        let glob = match !has_wildcard(glob) && Path::new(glob).is_dir() {
+            true => format!("{}/**", glob.trim_end_matches('/')),
+            false => glob.to_string(),
+        };
+
+        let pattern = Pattern::new(&glob).map_err(|err| format!("{glob}: {err}"))?;
+
+        let mut base = PathBuf::new();
+        let mut rest = vec![];
+        for component in Path::new(&glob).components() {
+            let text = component.as_os_str().to_string_lossy();
+            if rest.is_empty() && !has_wildcard(&text) {
+                base.push(component);
+            } else {
+                rest.push(text.to_string());
+            }
+        }
+
+        // A literal path names a file, so its directory is the one to watch.
+        if rest.is_empty() {
+            if let Some(name) = base.file_name() {
+                rest.push(name.to_string_lossy().to_string());
+            }
+            base.pop();
+        }
+
+        let depth = match rest.iter().any(|part| part == "**") {
+            true => None,
+            false => Some(rest.len().saturating_sub(1)),
+        };
+
+        Ok(Self {
+            pattern,
+            base,
+            depth,
+        })
+    }
+
+    fn wants_dir(&self, dir: &Path) -> bool {
+        let Ok(below) = dir.strip_prefix(&self.base) else {
+            return false;
+        };
+
+        match self.depth {
+            Some(depth) => below.components().count() <= depth,
+            None => true,
+        }
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        self.pattern.matches_path(path)
+    }
+}
+
+fn has_wildcard(text: &str) -> bool {
+    text.contains(['*', '?', '['])
+}
+
+/// Paths are kept relative, as they were given, so they match the globs.
+fn display_dir(dir: &Path) -> &Path {
+    match dir.as_os_str().is_empty() {
+        true => Path::new("."),
+        false => dir,
+    }
+}
+
+/// Wakes up the command thread when a file matching one of the globs changes.
+pub struct PathWatcher {
+    inotify: Inotify,
+    patterns: Vec<WatchPattern>,
+    dirs: HashMap<WatchDescriptor, PathBuf>,
+}
+
+impl PathWatcher {
+    pub fn new(globs: &[String]) -> Result<Self, String> {
+        let patterns = globs
+            .iter()
+            .map(|glob| WatchPattern::new(glob))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let inotify = Inotify::init(InitFlags::IN_CLOEXEC).map_err(|err| err.to_string())?;
+
+        let mut watcher = Self {
+            inotify,
+            patterns,
+            dirs: HashMap::new(),
+        };
+
+        let bases: Vec<PathBuf> = watcher.patterns.iter().map(|p| p.base.clone()).collect();
+        for base in bases {
+            if !display_dir(&base).is_dir() {
+                return Err(format!("{}: no such directory", base.display()));
+            }
+            watcher.add_dir(&base);
+        }
+
+        Ok(watcher)
+    }
+
+    /// Watch `dir` and the directories below it that a glob can reach.
+    /// Hidden directories below it are skipped.
+    fn add_dir(&mut self, dir: &Path) {
+        if !self.patterns.iter().any(|p| p.wants_dir(dir)) {
+            return;
+        }
+        if self.dirs.values().any(|watched| watched == dir) {
+            return;
+        }
+
+        match self.inotify.add_watch(display_dir(dir), WATCH_FLAGS) {
+            Ok(wd) => {
+                self.dirs.insert(wd, dir.to_path_buf());
+            }
+            Err(_) => return,
+        }
+
+        let Ok(entries) = fs::read_dir(display_dir(dir)) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if is_dir && !hidden {
+                self.add_dir(&dir.join(entry.file_name()));
+            }
+        }
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        // `./foo` and `foo` name the same file.
+        let path: PathBuf = path
+            .components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .collect();
+        self.patterns.iter().any(|p| p.matches(&path))
+    }
+
+    /// Read events until the inotify fd fails, sending every changed path
+    /// that matches a glob.
+    fn run(mut self, tx: Sender<PathBuf>) {
+        while let Ok(events) = self.inotify.read_events() {
+            for event in events {
+                let Some(dir) = self.dirs.get(&event.wd).cloned() else {
+                    continue;
+                };
+                let Some(name) = event.name else {
+                    continue;
+                };
+                let path = dir.join(name);
+
+                if event.mask.contains(AddWatchFlags::IN_ISDIR) {
+                    if event
+                        .mask
+                        .intersects(AddWatchFlags::IN_CREATE | AddWatchFlags::IN_MOVED_TO)
+                    {
+                        self.add_dir(&path);
+                    }
+                    continue;
+                }
+
+                if self.matches(&path) && tx.send(path).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    pub fn spawn(self) -> Receiver<PathBuf> {
+        let (tx, rx) = unbounded();
+        thread::spawn(move || self.run(tx));
+        rx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn watch_pattern_splits_base_and_depth() {
+        let pattern = WatchPattern::new("src/**/*.rs").unwrap();
+        assert_eq!(pattern.base, PathBuf::from("src"));
+        assert_eq!(pattern.depth, None);
+
+        let pattern = WatchPattern::new("src/*/mod.rs").unwrap();
+        assert_eq!(pattern.base, PathBuf::from("src"));
+        assert_eq!(pattern.depth, Some(1));
+
+        let pattern = WatchPattern::new("Makefile").unwrap();
+        assert_eq!(pattern.base, PathBuf::new());
+        assert_eq!(pattern.depth, Some(0));
+        assert!(pattern.wants_dir(Path::new("")));
+        assert!(!pattern.wants_dir(Path::new("src")));
+    }
+
+    #[test]
+    fn watch_pattern_treats_a_directory_as_everything_below_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let glob = dir.path().to_string_lossy().to_string();
+
+        let pattern = WatchPattern::new(&glob).unwrap();
+        assert_eq!(pattern.base, dir.path());
+        assert!(pattern.matches(&dir.path().join("a/b.txt")));
+    }
+
+    #[test]
+    fn path_watcher_sends_matching_changes_only() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        let glob = format!("{}/**/*.rs", dir.path().display());
+
+        let rx = PathWatcher::new(&[glob]).unwrap().spawn();
+
+        fs::write(dir.path().join("sub/notes.txt"), "x").unwrap();
+        fs::write(dir.path().join("sub/main.rs"), "x").unwrap();
+
+        let changed = rx.recv_timeout(Duration::from_secs(5)).ok();
+        assert_eq!(changed, Some(dir.path().join("sub/main.rs")));
+    }
+
+    #[test]
+    fn path_watcher_follows_new_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let glob = format!("{}/**/*.rs", dir.path().display());
+
+        let rx = PathWatcher::new(&[glob]).unwrap().spawn();
+
+        fs::create_dir(dir.path().join("new")).unwrap();
+        // Give the watcher a moment to pick up the new directory.
+        thread::sleep(Duration::from_millis(100));
+        fs::write(dir.path().join("new/lib.rs"), "x").unwrap();
+
+        let changed = rx.recv_timeout(Duration::from_secs(5)).ok();
+        assert_eq!(changed, Some(dir.path().join("new/lib.rs")));
+    }
+}
@@ -16,8 +16,8 @@ use tui::{
 use unicode_width::UnicodeWidthStr;
 
 // local module
-use crate::common::OutputMode;
-use crate::exec::CommandResult;
+use crate::common::{self, OutputMode};
+use crate::exec::{CommandResult, ResourceUsage};
 use crate::{
     app::{ActiveArea, InputMode},
     SharedInterval,
@@ -26,9 +26,18 @@ use hwatch_diffmode::{DiffMode, DiffModeOptions};
 
 //const
 // const POSITION_X_HELP_TEXT: usize = 47;
-const WIDTH_TEXT_INTERVAL: usize = 15;
 const WIDTH_TIMESTAMP: usize = 23; // "20XX-XX-XX XX:XX:XX.XXX".len() .. 19
 
+/// Logfile whose results are shown instead of those of a command.
+#[derive(Clone)]
+pub enum LogSource {
+    /// read once, with `--replay`
+    Replay(String),
+
+    /// tailed as another hwatch writes it, with `--follow-log`
+    Follow(String),
+}
+
 #[derive(Clone)]
 pub struct HeaderArea<'a> {
     ///
@@ -46,6 +55,27 @@ pub struct HeaderArea<'a> {
     ///
     exec_status: bool,
 
+    ///
+    exec_timed_out: bool,
+
+    /// exit code or signal, and how long the run took.
+    exec_status_text: String,
+
+    /// resource usage of the current result, to show the trend of the next one.
+    rusage: Option<ResourceUsage>,
+
+    /// the command is running right now.
+    is_running: bool,
+
+    /// attempt and number of attempts, while a failed run is retried.
+    retry: Option<(u32, u32)>,
+
+    /// logfile shown in place of the interval, when no command runs.
+    log_source: Option<LogSource>,
+
+    /// whether the latest result matches the baseline, when there is one.
+    baseline: Option<bool>,
+
     ///
     data: Vec<Line<'a>>,
 
@@ -97,6 +127,13 @@ impl HeaderArea<'_> {
             command: "".to_string(),
             timestamp: "".to_string(),
             exec_status: true,
+            exec_timed_out: false,
+            exec_status_text: "".to_string(),
+            rusage: None,
+            is_running: false,
+            retry: None,
+            log_source: None,
+            baseline: None,
 
             data: vec![Line::from("")],
             ansi_color: false,
@@ -147,9 +184,28 @@ impl HeaderArea<'_> {
     }
 
     pub fn set_current_result(&mut self, result: CommandResult) {
+        self.exec_status_text = status_text(&result, self.rusage.as_ref());
+        self.rusage = result.rusage;
         self.command = result.command;
         self.timestamp = result.timestamp;
         self.exec_status = result.status;
+        self.exec_timed_out = result.timed_out;
+    }
+
+    pub fn set_running(&mut self, is_running: bool) {
+        self.is_running = is_running;
+    }
+
+    pub fn set_retry(&mut self, retry: Option<(u32, u32)>) {
+        self.retry = retry;
+    }
+
+    pub fn set_log_source(&mut self, log_source: Option<LogSource>) {
+        self.log_source = log_source;
+    }
+
+    pub fn set_baseline(&mut self, baseline: Option<bool>) {
+        self.baseline = baseline;
     }
 
     pub fn set_diff_mode(&mut self, diff_mode: Arc<Mutex<Box<dyn DiffMode>>>) {
@@ -187,13 +243,47 @@ impl HeaderArea<'_> {
         let command_width: usize;
         let timestamp_width: usize;
         // WIDTH_TIMESTAMP ... timestamp width
-        // WIDTH_TEXT_INTERVAL ... interval sec width
+        // interval_width ... schedule and next run time width
         // 2 ... space
         // 1 ... `:`
         // self.banner.len() ... banner length
         // 1 ... space
-        let command_width_offset =
-            WIDTH_TEXT_INTERVAL + (2 + 1 + self.banner.len() + 1 + WIDTH_TIMESTAMP);
+        // status_text.len() ... exit status and duration, or the running indicator
+        // baseline_text.len() ... whether the output matches the baseline
+        let (status_text, status_color) = match (self.is_running, self.retry) {
+            (true, Some((attempt, attempts))) => (
+                format!(" [retry {attempt}/{attempts}]"),
+                Some(Color::Yellow),
+            ),
+            (true, None) => (" [running]".to_string(), Some(Color::Cyan)),
+            (false, _) => (self.exec_status_text.clone(), None),
+        };
+        let (baseline_text, baseline_color) = match self.baseline {
+            Some(true) => (" [= baseline]", Color::Green),
+            Some(false) => (" [≠ baseline]", Color::Red),
+            None => ("", Color::Reset),
+        };
+        let run_interval = self.interval.read().unwrap();
+        // Get the data to display at header.
+        let (schedule, interval) = match (&self.log_source, run_interval.paused) {
+            (Some(LogSource::Replay(logfile)), _) => ("Replay ", logfile.clone()),
+            (Some(LogSource::Follow(logfile)), _) => ("Follow ", logfile.clone()),
+            (None, true) => ("Every ", "Paused".into()),
+            (None, false) => ("Every ", run_interval.label()),
+        };
+        let next_run = match (&self.log_source, run_interval.paused, run_interval.next_run) {
+            (None, false, Some(next_run)) => format!(" next {}", next_run.format("%H:%M:%S")),
+            _ => "".to_string(),
+        };
+        drop(run_interval);
+
+        // "Every " + interval (at least 9 wide) + next run
+        let interval_width = schedule.len() + interval.width().max(9) + next_run.len();
+
+        let command_width_offset = interval_width
+            + (2 + 1 + self.banner.len() + 1 + WIDTH_TIMESTAMP)
+            + status_text.width()
+            + baseline_text.width();
         if command_width_offset < width {
             command_width = width - command_width_offset;
             timestamp_width = WIDTH_TIMESTAMP;
@@ -202,13 +292,6 @@ impl HeaderArea<'_> {
             timestamp_width = 0;
         }
 
-        let interval = self.interval.read().unwrap();
-        // Get the data to display at header.
-        let interval = match interval.paused {
-            true => "Paused".into(),
-            false => format!("{:.3}", interval.interval),
-        };
-
         // Set Number flag value
         let value_number: Span = match self.line_number {
             true => Span::styled(
@@ -297,23 +380,30 @@ impl HeaderArea<'_> {
         }
 
         // Set Color
-        let command_color = match self.exec_status {
-            true => Color::Green,
-            false => Color::Red,
+        let command_color = match (self.exec_status, self.exec_timed_out) {
+            (_, true) => Color::Yellow,
+            (true, _) => Color::Green,
+            (false, _) => Color::Red,
         };
 
         // Create 1st line.
         self.data.push(Line::from(vec![
-            Span::raw("Every "),
+            Span::raw(schedule),
             Span::styled(
                 format!("{:>wid$}", interval, wid = 9),
                 Style::default().fg(Color::Cyan),
             ),
+            Span::raw(next_run),
             Span::raw(":"),
             Span::styled(
                 format!("{:wid$}", self.command, wid = command_width),
                 Style::default().fg(command_color),
             ),
+            Span::styled(
+                status_text,
+                Style::default().fg(status_color.unwrap_or(command_color)),
+            ),
+            Span::styled(baseline_text, Style::default().fg(baseline_color)),
             Span::raw(" "),
             Span::styled(
                 self.banner.clone(),
@@ -388,6 +478,71 @@ impl HeaderArea<'_> {
     }
 }
 
+// e.g. ` [exit 1, 0.012s]`, ` [SIGSEGV, 2.000s]`, ` [timeout, 5.000s]`
+fn status_text(result: &CommandResult, previous: Option<&ResourceUsage>) -> String {
+    let exit_text = result.get_exit_text();
+    let mut items = vec![];
+    if !exit_text.is_empty() {
+        if result.exit_code.is_some() && !result.timed_out {
+            items.push(format!("exit {exit_text}"));
+        } else {
+            items.push(exit_text);
+        }
+    }
+    if let Some(duration) = result.get_duration() {
+        items.push(common::format_duration(duration));
+    }
+    if let Some(rusage) = &result.rusage {
+        items.push(usage_text(rusage, previous));
+    }
+    if result.truncated {
+        items.push("truncated".to_string());
+    }
+    if result.attempts > 1 {
+        items.push(format!("attempt {}", result.attempts));
+    }
+    if !result.trigger.is_empty() {
+        items.push(format!("by {}", result.trigger));
+    }
+
+    if items.is_empty() {
+        return "".to_string();
+    }
+    format!(" [{}]", items.join(", "))
+}
+
+// e.g. `cpu 0.120s▲ rss 12.3M`, with an arrow on the values that went up or
+// down since the previous run.
+fn usage_text(current: &ResourceUsage, previous: Option<&ResourceUsage>) -> String {
+    let cpu = common::format_duration(current.cpu_time());
+    let rss = common::format_kib(current.max_rss);
+    let (cpu_trend, rss_trend) = match previous {
+        Some(previous) => (
+            trend(
+                &cpu,
+                &common::format_duration(previous.cpu_time()),
+                current.cpu_time() > previous.cpu_time(),
+            ),
+            trend(
+                &rss,
+                &common::format_kib(previous.max_rss),
+                current.max_rss > previous.max_rss,
+            ),
+        ),
+        None => ("", ""),
+    };
+    format!("cpu {cpu}{cpu_trend} rss {rss}{rss_trend}")
+}
+
+// A change too small to show up in the formatted value gets no arrow.
+fn trend(current: &str, previous: &str, is_up: bool) -> &'static str {
+    match (current == previous, is_up) {
+        (true, _) => "",
+        (false, true) => "▲",
+        (false, false) => "▼",
+    }
+}
+
 fn format_with_multibyte_width(input: &str, target_width: usize) -> String {
     let current_width = UnicodeWidthStr::width(input);
     if current_width >= target_width {
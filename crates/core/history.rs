@@ -7,6 +7,7 @@ mod summary;
 
 #[cfg(test)]
 use self::summary::calc_char_diff;
+use std::time::Duration;
 use tui::{
     layout::Constraint,
     style::{Color, Modifier, Style},
@@ -16,6 +17,81 @@ use tui::{
     Frame,
 };
 
+use crate::common;
+use crate::exec::CommandResult;
+
+/// Optional columns displayed to the right of the timestamp.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryColumn {
+    Exit,
+    Duration,
+    Flaps,
+    Runs,
+    Cpu,
+    Rss,
+    Stdin,
+    Truncated,
+}
+
+impl HistoryColumn {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "exit" => Some(HistoryColumn::Exit),
+            "duration" => Some(HistoryColumn::Duration),
+            "flaps" => Some(HistoryColumn::Flaps),
+            "runs" => Some(HistoryColumn::Runs),
+            "cpu" => Some(HistoryColumn::Cpu),
+            "rss" => Some(HistoryColumn::Rss),
+            "stdin" => Some(HistoryColumn::Stdin),
+            "truncated" => Some(HistoryColumn::Truncated),
+            _ => None,
+        }
+    }
+
+    fn width(&self) -> u16 {
+        match self {
+            HistoryColumn::Exit => 7,
+            HistoryColumn::Duration => 7,
+            HistoryColumn::Flaps => 5,
+            HistoryColumn::Runs => 20,
+            HistoryColumn::Cpu => 7,
+            HistoryColumn::Rss => 7,
+            HistoryColumn::Stdin => 8,
+            HistoryColumn::Truncated => 5,
+        }
+    }
+
+    fn text(&self, history: &History) -> String {
+        match self {
+            HistoryColumn::Exit => {
+                common::exit_status_text(history.timed_out, history.exit_code, history.signal)
+            }
+            HistoryColumn::Duration => history
+                .duration
+                .map(common::format_duration)
+                .unwrap_or_default(),
+            HistoryColumn::Flaps => match history.flaps {
+                0 => String::new(),
+                flaps => format!("~{flaps}"),
+            },
+            HistoryColumn::Runs => match history.runs {
+                0 | 1 => String::new(),
+                runs => format!("x{runs}, last {}", time_of_day(&history.last_seen)),
+            },
+            HistoryColumn::Cpu => history
+                .cpu_time
+                .map(common::format_duration)
+                .unwrap_or_default(),
+            HistoryColumn::Rss => history.max_rss.map(common::format_kib).unwrap_or_default(),
+            HistoryColumn::Stdin => history.stdin_change.clone().unwrap_or_default(),
+            HistoryColumn::Truncated => match history.truncated {
+                true => "trunc".to_string(),
+                false => String::new(),
+            },
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
 pub struct History {
     /// timestamp
@@ -24,12 +100,72 @@ pub struct History {
     /// result status
     pub status: bool,
 
+    /// the command was killed by `--timeout`
+    pub timed_out: bool,
+
+    /// exit code, if the command exited normally
+    pub exit_code: Option<i32>,
+
+    /// signal that terminated the command
+    pub signal: Option<i32>,
+
+    /// wall-clock time of the run
+    pub duration: Option<Duration>,
+
+    /// user and system CPU time of the run
+    pub cpu_time: Option<Duration>,
+
+    /// peak resident set size of the run, in KiB
+    pub max_rss: Option<u64>,
+
+    /// output was cut at `--max-output-bytes`
+    pub truncated: bool,
+
     /// history number.
     /// This value will be the same as the index number of App.result in `app.rs``.
     pub num: u16,
 
     /// summary
     pub summary: HistorySummary,
+
+    /// changes that did not last for `--stable-for` runs while this was the latest result
+    pub flaps: u32,
+
+    /// runs with this output, including the unchanged ones after it
+    pub runs: u32,
+
+    /// timestamp of the last of those runs
+    pub last_seen: String,
+
+    /// hash of the `--stdin-file` content, if it differs from the entry before
+    pub stdin_change: Option<String>,
+}
+
+impl History {
+    pub fn from_result(num: u16, result: &CommandResult, summary: HistorySummary) -> Self {
+        Self {
+            timestamp: result.timestamp.clone(),
+            status: result.status,
+            timed_out: result.timed_out,
+            exit_code: result.exit_code,
+            signal: result.signal,
+            duration: result.get_duration(),
+            cpu_time: result.rusage.map(|rusage| rusage.cpu_time()),
+            max_rss: result.rusage.map(|rusage| rusage.max_rss),
+            truncated: result.truncated,
+            num,
+            summary,
+            flaps: 0,
+            runs: 1,
+            last_seen: result.timestamp.clone(),
+            stdin_change: None,
+        }
+    }
+}
+
+/// `HH:MM:SS` part of a result timestamp.
+fn time_of_day(timestamp: &str) -> &str {
+    timestamp.get(11..19).unwrap_or(timestamp)
 }
 
 #[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
@@ -81,6 +217,12 @@ pub struct HistoryArea {
 
     /// enable character diff
     enable_char_diff: bool,
+
+    /// extra columns
+    columns: Vec<HistoryColumn>,
+
+    /// history number the watch pane diffs against
+    diff_base: Option<usize>,
 }
 
 /// History Area Object Trait
@@ -94,8 +236,19 @@ impl HistoryArea {
             data: vec![vec![History {
                 timestamp: "latest                 ".to_string(),
                 status: true,
+                timed_out: false,
+                exit_code: None,
+                signal: None,
+                duration: None,
+                cpu_time: None,
+                max_rss: None,
+                truncated: false,
                 num: 0,
                 summary: HistorySummary::init(),
+                flaps: 0,
+                runs: 0,
+                last_seen: String::new(),
+                stdin_change: None,
             }]],
             state: TableState::default(),
             summary: false,
@@ -103,9 +256,30 @@ impl HistoryArea {
             hide_header: false,
             scroll_bar: false,
             enable_char_diff: false,
+            columns: vec![],
+            diff_base: None,
         }
     }
 
+    ///
+    pub fn set_diff_base(&mut self, diff_base: Option<usize>) {
+        self.diff_base = diff_base;
+    }
+
+    ///
+    pub fn set_columns(&mut self, columns: Vec<HistoryColumn>) {
+        self.columns = columns;
+    }
+
+    /// Width of the pane, including the extra columns.
+    pub fn get_width(&self) -> u16 {
+        self.columns
+            .iter()
+            .fold(crate::HISTORY_WIDTH, |width, column| {
+                width + column.width() + 1
+            })
+    }
+
     ///
     pub fn set_enable_char_diff(&mut self, enable_char_diff: bool) {
         self.enable_char_diff = enable_char_diff;
@@ -122,8 +296,9 @@ impl HistoryArea {
     }
 
     ///
-    pub fn set_latest_status(&mut self, latest_status: bool) {
+    pub fn set_latest_status(&mut self, latest_status: bool, latest_timed_out: bool) {
         self.data[0][0].status = latest_status;
+        self.data[0][0].timed_out = latest_timed_out;
     }
 
     ///
@@ -147,26 +322,31 @@ impl HistoryArea {
     }
 
     ///
-    pub fn update(
-        &mut self,
-        timestamp: String,
-        status: bool,
-        num: u16,
-        history_summary: HistorySummary,
-    ) {
+    pub fn update(&mut self, history: History) {
         // set result statu to latest
-        self.set_latest_status(status);
+        self.set_latest_status(history.status, history.timed_out);
 
         // insert latest timestamp
-        self.data.insert(
-            1,
-            vec![History {
-                timestamp,
-                status,
-                num,
-                summary: history_summary,
-            }],
-        );
+        self.data.insert(1, vec![history]);
+    }
+
+    /// Update the flap counter of a row.
+    pub fn set_flaps(&mut self, index: usize, flaps: u32) {
+        for d in self.data.iter_mut() {
+            if d[0].num == index as u16 {
+                d[0].flaps = flaps;
+            }
+        }
+    }
+
+    /// Update the run counter of a row.
+    pub fn set_runs(&mut self, index: usize, runs: u32, last_seen: &str) {
+        for d in self.data.iter_mut() {
+            if d[0].num == index as u16 {
+                d[0].runs = runs;
+                d[0].last_seen = last_seen.to_string();
+            }
+        }
     }
 
     ///
@@ -226,14 +406,29 @@ impl HistoryArea {
                 // cell style
                 let cell_style = Style::default().fg(match ix {
                     0 => LATEST_COLOR,
-                    _ => match c.status {
-                        true => Color::Green,
-                        false => Color::Red,
+                    _ => match (c.status, c.timed_out) {
+                        (_, true) => Color::Yellow,
+                        (true, _) => Color::Green,
+                        (false, _) => Color::Red,
                     },
                 });
 
-                // line1: timestamp
-                let line1 = Line::from(vec![Span::styled(c.timestamp.as_str(), cell_style)]);
+                // line1: timestamp and extra columns
+                // the diff base is shown in reverse video
+                let timestamp_style = match ix != 0 && self.diff_base == Some(c.num as usize) {
+                    true => cell_style.add_modifier(Modifier::REVERSED),
+                    false => cell_style,
+                };
+                let mut line1_spans = vec![Span::styled(c.timestamp.clone(), timestamp_style)];
+                if ix != 0 {
+                    for column in &self.columns {
+                        line1_spans.push(Span::styled(
+                            format!(" {:>wid$}", column.text(c), wid = column.width() as usize),
+                            cell_style,
+                        ));
+                    }
+                }
+                let line1 = Line::from(line1_spans);
 
                 // line2: line summary
                 let line2 = Line::from(vec![
@@ -296,7 +491,7 @@ impl HistoryArea {
         let pane_block: Block<'_>;
         let history_width: u16;
         if self.border {
-            history_width = crate::HISTORY_WIDTH + 1;
+            history_width = self.get_width() + 1;
             if self.hide_header {
                 pane_block = Block::default();
             } else {
@@ -309,7 +504,7 @@ impl HistoryArea {
                     });
             }
         } else {
-            history_width = crate::HISTORY_WIDTH;
+            history_width = self.get_width();
             pane_block = Block::default()
         }
 
@@ -472,6 +667,116 @@ mod tests {
         assert_eq!(summary.char_rem, 0);
     }
 
+    fn history(timestamp: String, status: bool, num: u16, summary: HistorySummary) -> History {
+        History {
+            timestamp,
+            status,
+            timed_out: false,
+            exit_code: None,
+            signal: None,
+            duration: None,
+            cpu_time: None,
+            max_rss: None,
+            truncated: false,
+            num,
+            summary,
+            flaps: 0,
+            runs: 1,
+            last_seen: String::new(),
+            stdin_change: None,
+        }
+    }
+
+    #[test]
+    fn history_area_width_grows_with_columns() {
+        let mut area = HistoryArea::new();
+        assert_eq!(area.get_width(), crate::HISTORY_WIDTH);
+
+        area.set_columns(vec![HistoryColumn::Exit, HistoryColumn::Duration]);
+        assert_eq!(area.get_width(), crate::HISTORY_WIDTH + 16);
+    }
+
+    #[test]
+    fn history_column_text_formats_exit_and_duration() {
+        let mut entry = history(
+            "2026-04-08 12:00:00.000".to_string(),
+            false,
+            1,
+            HistorySummary::init(),
+        );
+        entry.exit_code = Some(2);
+        entry.duration = Some(Duration::from_millis(1500));
+        assert_eq!(HistoryColumn::Exit.text(&entry), "2");
+        assert_eq!(HistoryColumn::Duration.text(&entry), "1.500s");
+
+        entry.exit_code = None;
+        entry.signal = Some(9);
+        assert_eq!(HistoryColumn::Exit.text(&entry), "SIGKILL");
+
+        entry.timed_out = true;
+        assert_eq!(HistoryColumn::Exit.text(&entry), "timeout");
+    }
+
+    #[test]
+    fn history_column_text_formats_runs() {
+        let mut entry = history(
+            "2026-04-08 12:00:00.000".to_string(),
+            true,
+            1,
+            HistorySummary::init(),
+        );
+        assert_eq!(HistoryColumn::Runs.text(&entry), "");
+
+        entry.runs = 37;
+        entry.last_seen = "2026-04-08 12:03:10.250".to_string();
+        assert_eq!(HistoryColumn::Runs.text(&entry), "x37, last 12:03:10");
+    }
+
+    #[test]
+    fn history_column_text_formats_resource_usage() {
+        let mut entry = history(
+            "2026-04-08 12:00:00.000".to_string(),
+            true,
+            1,
+            HistorySummary::init(),
+        );
+        assert_eq!(HistoryColumn::Cpu.text(&entry), "");
+        assert_eq!(HistoryColumn::Rss.text(&entry), "");
+
+        entry.cpu_time = Some(Duration::from_millis(250));
+        entry.max_rss = Some(4096);
+        assert_eq!(HistoryColumn::Cpu.text(&entry), "0.250s");
+        assert_eq!(HistoryColumn::Rss.text(&entry), "4.0M");
+    }
+
+    #[test]
+    fn history_column_text_shows_changed_stdin() {
+        let mut entry = history(
+            "2026-04-08 12:00:00.000".to_string(),
+            true,
+            1,
+            HistorySummary::init(),
+        );
+        assert_eq!(HistoryColumn::Stdin.text(&entry), "");
+
+        entry.stdin_change = Some("1c291ca3".to_string());
+        assert_eq!(HistoryColumn::Stdin.text(&entry), "1c291ca3");
+    }
+
+    #[test]
+    fn history_column_text_flags_truncated_output() {
+        let mut entry = history(
+            "2026-04-08 12:00:00.000".to_string(),
+            true,
+            1,
+            HistorySummary::init(),
+        );
+        assert_eq!(HistoryColumn::Truncated.text(&entry), "");
+
+        entry.truncated = true;
+        assert_eq!(HistoryColumn::Truncated.text(&entry), "trunc");
+    }
+
     #[test]
     fn history_area_update_delete_and_selection_follow_history_numbers() {
         let mut area = HistoryArea::new();
@@ -482,13 +787,18 @@ mod tests {
             char_rem: 4,
         };
 
-        area.update(
+        area.update(history(
             "2026-04-08 12:00:00.000".to_string(),
             false,
             5,
             summary.clone(),
-        );
-        area.update("2026-04-08 12:00:01.000".to_string(), true, 6, summary);
+        ));
+        area.update(history(
+            "2026-04-08 12:00:01.000".to_string(),
+            true,
+            6,
+            summary,
+        ));
         area.set_state_select(5);
 
         assert_eq!(area.get_history_size(), 3);
@@ -505,13 +815,18 @@ mod tests {
     fn history_area_next_and_previous_stay_within_bounds() {
         let mut area = HistoryArea::new();
         let summary = HistorySummary::init();
-        area.update(
+        area.update(history(
             "2026-04-08 12:00:00.000".to_string(),
             true,
             1,
             summary.clone(),
-        );
-        area.update("2026-04-08 12:00:01.000".to_string(), true, 2, summary);
+        ));
+        area.update(history(
+            "2026-04-08 12:00:01.000".to_string(),
+            true,
+            2,
+            summary,
+        ));
 
         area.set_state_select(2);
         area.next(1);
@@ -525,13 +840,18 @@ mod tests {
     fn history_area_click_row_accounts_for_summary_rows() {
         let mut area = HistoryArea::new();
         let summary = HistorySummary::init();
-        area.update(
+        area.update(history(
             "2026-04-08 12:00:00.000".to_string(),
             true,
             1,
             summary.clone(),
-        );
-        area.update("2026-04-08 12:00:01.000".to_string(), true, 2, summary);
+        ));
+        area.update(history(
+            "2026-04-08 12:00:01.000".to_string(),
+            true,
+            2,
+            summary,
+        ));
         area.set_summary(true);
         area.set_enable_char_diff(true);
 
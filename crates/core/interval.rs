@@ -2,10 +2,39 @@
 // Use of this source code is governed by an MIT license
 // that can be found in the LICENSE file.
 
+#[path = "interval_backoff.rs"]
+mod backoff;
+#[path = "interval_cron.rs"]
+mod cron;
+
+use chrono::{DateTime, Duration, Local, LocalResult, NaiveDateTime, TimeZone};
+
+use crate::exec::CommandResult;
+
+pub use self::backoff::Backoff;
+pub use self::cron::CronSchedule;
+
+/// When runs are due.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScheduleKind {
+    /// `interval` seconds after the previous run.
+    Interval,
+    /// On multiples of `interval` counted from local midnight.
+    Align,
+    /// On the minutes matched by a cron expression.
+    Cron(CronSchedule),
+}
+
 #[derive(Clone, Debug)]
 pub struct RunInterval {
+    /// base interval set by the user, see `effective_interval` for the one in use.
     pub interval: f64,
     pub paused: bool,
+    pub kind: ScheduleKind,
+    pub backoff: Option<Backoff>,
+
+    /// set by the command thread when it goes to sleep.
+    pub next_run: Option<DateTime<Local>>,
 }
 
 impl RunInterval {
@@ -13,6 +42,82 @@ impl RunInterval {
         Self {
             interval,
             paused: false,
+            kind: ScheduleKind::Interval,
+            backoff: None,
+            next_run: None,
+        }
+    }
+
+    pub fn set_kind(&mut self, kind: ScheduleKind) {
+        self.kind = kind;
+    }
+
+    pub fn set_backoff(&mut self, backoff: Backoff) {
+        self.backoff = Some(backoff);
+    }
+
+    /// The base interval, stretched by the backoff policy if there is one.
+    pub fn effective_interval(&self) -> f64 {
+        match &self.backoff {
+            Some(backoff) => backoff.apply(self.interval),
+            None => self.interval,
+        }
+    }
+
+    /// Let the backoff policy see a finished run.
+    pub fn record_result(&mut self, result: &CommandResult) {
+        if let Some(backoff) = &mut self.backoff {
+            backoff.record(result);
+        }
+    }
+
+    /// Short description of the schedule for the header.
+    pub fn label(&self) -> String {
+        let interval = self.effective_interval();
+        let interval = match interval == self.interval {
+            true => format!("{interval:.3}"),
+            false => format!("{interval:.3} (base {:.3})", self.interval),
+        };
+
+        match &self.kind {
+            ScheduleKind::Interval => interval,
+            ScheduleKind::Align => format!("{interval} aligned"),
+            ScheduleKind::Cron(schedule) => schedule.expression().to_string(),
+        }
+    }
+
+    /// The next wall-clock run after `now`. `None` for `ScheduleKind::Interval`,
+    /// whose runs are counted from the previous one, and for an aligned
+    /// interval that is not a positive number of seconds.
+    pub fn next_scheduled(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        match &self.kind {
+            ScheduleKind::Interval => None,
+            ScheduleKind::Align => {
+                let interval = self.effective_interval();
+                if !interval.is_finite() || interval <= 0.0 {
+                    return None;
+                }
+                let midnight = to_local(now.date_naive().and_hms_opt(0, 0, 0)?)?;
+                let elapsed = (now - midnight).num_milliseconds() as f64 / 1000.0;
+                let count = (elapsed / interval).floor() + 1.0;
+                let next = midnight + Duration::milliseconds((count * interval * 1000.0) as i64);
+
+                // Start over at the next midnight when the interval does not divide a day.
+                let next_midnight = to_local(now.date_naive().succ_opt()?.and_hms_opt(0, 0, 0)?)?;
+                Some(next.min(next_midnight))
+            }
+            ScheduleKind::Cron(schedule) => {
+                let mut after = now.naive_local();
+                // Skip times that do not exist locally (DST gaps).
+                for _ in 0..4 {
+                    let next = schedule.next_after(after)?;
+                    if let Some(next) = to_local(next) {
+                        return Some(next);
+                    }
+                    after = next;
+                }
+                None
+            }
         }
     }
 
@@ -31,6 +136,14 @@ impl RunInterval {
     }
 }
 
+fn to_local(time: NaiveDateTime) -> Option<DateTime<Local>> {
+    match Local.from_local_datetime(&time) {
+        LocalResult::Single(time) => Some(time),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest),
+        LocalResult::None => None,
+    }
+}
+
 impl Default for RunInterval {
     fn default() -> Self {
         Self::new(2.0)
@@ -39,7 +152,7 @@ impl Default for RunInterval {
 
 #[cfg(test)]
 mod tests {
-    use super::RunInterval;
+    use super::*;
 
     #[test]
     fn test_run_interval() {
@@ -65,4 +178,73 @@ mod tests {
         assert_eq!(actual.interval, 1.0);
         assert!(!actual.paused);
     }
+
+    fn local(text: &str) -> DateTime<Local> {
+        to_local(NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.3f").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn align_runs_on_multiples_of_the_interval() {
+        let mut actual = RunInterval::new(60.0);
+        actual.set_kind(ScheduleKind::Align);
+
+        assert_eq!(
+            actual.next_scheduled(local("2026-04-08 12:00:30.250")),
+            Some(local("2026-04-08 12:01:00.000"))
+        );
+        assert_eq!(
+            actual.next_scheduled(local("2026-04-08 12:01:00.000")),
+            Some(local("2026-04-08 12:02:00.000"))
+        );
+        assert_eq!(actual.label(), "60.000 aligned");
+
+        // 7 hours do not divide a day, so the last slot ends at midnight.
+        actual.interval = 7.0 * 3600.0;
+        assert_eq!(
+            actual.next_scheduled(local("2026-04-08 22:00:00.000")),
+            Some(local("2026-04-09 00:00:00.000"))
+        );
+    }
+
+    #[test]
+    fn align_has_no_slot_without_a_positive_interval() {
+        let now = local("2026-04-08 12:00:30.250");
+        for interval in [0.0, -1.0, f64::NAN] {
+            let mut actual = RunInterval::new(interval);
+            actual.set_kind(ScheduleKind::Align);
+            assert_eq!(actual.next_scheduled(now), None);
+        }
+    }
+
+    #[test]
+    fn backoff_stretches_the_effective_interval_only() {
+        let mut actual = RunInterval::new(2.0);
+        actual.set_backoff(Backoff::new(2.0, 30.0));
+
+        let failed = CommandResult {
+            status: false,
+            ..Default::default()
+        };
+        actual.record_result(&failed);
+        actual.increase(1.0);
+
+        assert_eq!(actual.interval, 3.0);
+        assert_eq!(actual.effective_interval(), 6.0);
+        assert_eq!(actual.label(), "6.000 (base 3.000)");
+    }
+
+    #[test]
+    fn cron_schedule_is_used_for_the_next_run() {
+        let mut actual = RunInterval::default();
+        assert_eq!(actual.next_scheduled(Local::now()), None);
+
+        actual.set_kind(ScheduleKind::Cron(
+            CronSchedule::parse("*/5 * * * *").unwrap(),
+        ));
+        assert_eq!(
+            actual.next_scheduled(local("2026-04-08 12:03:10.000")),
+            Some(local("2026-04-08 12:05:00.000"))
+        );
+        assert_eq!(actual.label(), "*/5 * * * *");
+    }
 }
@@ -0,0 +1,142 @@
+// Copyright (c) 2026 Blacknon. All rights reserved.
+// Use of this source code is governed by an MIT license
+// that can be found in the LICENSE file.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::exec::CommandResult;
+
+/// Stretches the interval after consecutive failures, and optionally while
+/// the output stays the same.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Backoff {
+    factor: f64,
+    max: f64,
+
+    /// start stretching after this many unchanged runs
+    unchanged_after: Option<u32>,
+
+    failures: u32,
+    unchanged: u32,
+    last_output: Option<u64>,
+}
+
+impl Backoff {
+    pub fn new(factor: f64, max: f64) -> Self {
+        Self {
+            factor,
+            max,
+            unchanged_after: None,
+            failures: 0,
+            unchanged: 0,
+            last_output: None,
+        }
+    }
+
+    pub fn set_unchanged_after(mut self, runs: Option<u32>) -> Self {
+        self.unchanged_after = runs;
+        self
+    }
+
+    /// Count a finished run. A success resets the failures, and a changed
+    /// output resets the unchanged runs.
+    pub fn record(&mut self, result: &CommandResult) {
+        match result.status {
+            true => self.failures = 0,
+            false => self.failures += 1,
+        }
+
+        let mut hasher = DefaultHasher::new();
+        result.get_output().hash(&mut hasher);
+        let output = hasher.finish();
+
+        match self.last_output == Some(output) {
+            true => self.unchanged += 1,
+            false => self.unchanged = 0,
+        }
+        self.last_output = Some(output);
+    }
+
+    fn steps(&self) -> u32 {
+        let unchanged = match self.unchanged_after {
+            Some(after) if self.unchanged >= after => self.unchanged - after + 1,
+            _ => 0,
+        };
+
+        // A failing command usually prints the same error each time, so the
+        // two are not added up.
+        self.failures.max(unchanged)
+    }
+
+    /// The interval to wait instead of `interval`. Never shorter than `interval`.
+    pub fn apply(&self, interval: f64) -> f64 {
+        match self.steps() {
+            0 => interval,
+            steps => (interval * self.factor.powi(steps as i32))
+                .min(self.max)
+                .max(interval),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(status: bool, output: &str) -> CommandResult {
+        CommandResult {
+            status,
+            ..Default::default()
+        }
+        .set_output(output.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn failures_stretch_the_interval_until_a_success() {
+        let mut backoff = Backoff::new(2.0, 10.0);
+        assert_eq!(backoff.apply(2.0), 2.0);
+
+        backoff.record(&result(false, "a"));
+        assert_eq!(backoff.apply(2.0), 4.0);
+        backoff.record(&result(false, "b"));
+        assert_eq!(backoff.apply(2.0), 8.0);
+        backoff.record(&result(false, "c"));
+        assert_eq!(backoff.apply(2.0), 10.0);
+
+        backoff.record(&result(true, "d"));
+        assert_eq!(backoff.apply(2.0), 2.0);
+    }
+
+    #[test]
+    fn unchanged_output_stretches_the_interval_after_n_runs() {
+        let mut backoff = Backoff::new(2.0, 60.0).set_unchanged_after(Some(2));
+
+        backoff.record(&result(true, "same"));
+        backoff.record(&result(true, "same"));
+        assert_eq!(backoff.apply(1.0), 1.0);
+        backoff.record(&result(true, "same"));
+        assert_eq!(backoff.apply(1.0), 2.0);
+        backoff.record(&result(true, "same"));
+        assert_eq!(backoff.apply(1.0), 4.0);
+
+        backoff.record(&result(true, "changed"));
+        assert_eq!(backoff.apply(1.0), 1.0);
+    }
+
+    #[test]
+    fn unchanged_output_is_ignored_without_unchanged_after() {
+        let mut backoff = Backoff::new(2.0, 60.0);
+        for _ in 0..5 {
+            backoff.record(&result(true, "same"));
+        }
+        assert_eq!(backoff.apply(1.0), 1.0);
+    }
+
+    #[test]
+    fn max_below_the_interval_keeps_the_interval() {
+        let mut backoff = Backoff::new(2.0, 1.0);
+        backoff.record(&result(false, "a"));
+        assert_eq!(backoff.apply(5.0), 5.0);
+    }
+}
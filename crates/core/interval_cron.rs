@@ -0,0 +1,250 @@
+// Copyright (c) 2026 Blacknon. All rights reserved.
+// Use of this source code is governed by an MIT license
+// that can be found in the LICENSE file.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+
+// How far ahead to look for a matching time before giving up.
+const SEARCH_LIMIT_DAYS: i64 = 366 * 5;
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// A five field cron expression (`minute hour day-of-month month day-of-week`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+
+    /// day-of-month or day-of-week was `*`. Otherwise a day matches when
+    /// either field does, as in cron.
+    any_day: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expanded = match expression.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "'{expression}' must have 5 fields (minute hour day-of-month month day-of-week)"
+            ));
+        }
+
+        let weekdays = parse_field(fields[4], 0, 7, &WEEKDAY_NAMES, 0)?;
+        // Both 0 and 7 are Sunday.
+        let weekdays = (weekdays | (weekdays >> 7)) & 0x7f;
+
+        Ok(Self {
+            expression: expression.trim().to_string(),
+            minutes: parse_field(fields[0], 0, 59, &[], 0)?,
+            hours: parse_field(fields[1], 0, 23, &[], 0)?,
+            days: parse_field(fields[2], 1, 31, &[], 0)?,
+            months: parse_field(fields[3], 1, 12, &MONTH_NAMES, 1)?,
+            weekdays,
+            any_day: fields[2].starts_with('*') || fields[4].starts_with('*'),
+        })
+    }
+
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day = bit(self.days, date.day());
+        let weekday = bit(self.weekdays, date.weekday().num_days_from_sunday());
+
+        match self.any_day {
+            true => day && weekday,
+            false => day || weekday,
+        }
+    }
+
+    /// The first matching minute after `now`.
+    pub fn next_after(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut time = now.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = time + Duration::days(SEARCH_LIMIT_DAYS);
+
+        while time < limit {
+            let date = time.date();
+
+            if !bit(self.months, date.month()) {
+                let (year, month) = match date.month() {
+                    12 => (date.year() + 1, 1),
+                    month => (date.year(), month + 1),
+                };
+                time = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+
+            if !self.matches_day(date) {
+                time = date.succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+
+            if !bit(self.hours, time.hour()) {
+                time = date.and_hms_opt(time.hour(), 0, 0)? + Duration::hours(1);
+                continue;
+            }
+
+            if !bit(self.minutes, time.minute()) {
+                time += Duration::minutes(1);
+                continue;
+            }
+
+            return Some(time);
+        }
+
+        None
+    }
+}
+
+fn bit(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+/// Parse one field (`*`, `*/n`, `a`, `a-b`, `a-b/n`, comma separated) into a
+/// bit set. `names` are accepted in place of numbers, counting from `name_base`.
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    name_base: u32,
+) -> Result<u64, String> {
+    let value = |text: &str| -> Result<u32, String> {
+        let lower = text.to_ascii_lowercase();
+        if let Some(index) = names.iter().position(|name| *name == lower) {
+            return Ok(index as u32 + name_base);
+        }
+
+        match text.parse::<u32>() {
+            Ok(value) if (min..=max).contains(&value) => Ok(value),
+            _ => Err(format!("'{text}' is not a value between {min} and {max}")),
+        }
+    };
+
+    let mut set = 0;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(format!("'{step}' is not a valid step")),
+            },
+            None => (item, 1),
+        };
+
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `a/n` runs from `a` to the end of the range.
+                None if item.contains('/') => (value(range)?, max),
+                None => {
+                    let value = value(range)?;
+                    (value, value)
+                }
+            },
+        };
+
+        if start > end {
+            return Err(format!("'{range}' is not a valid range"));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    fn next(expression: &str, now: &str) -> String {
+        CronSchedule::parse(expression)
+            .unwrap()
+            .next_after(time(now))
+            .unwrap()
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    }
+
+    #[test]
+    fn next_after_steps_and_ranges() {
+        assert_eq!(
+            next("*/5 * * * *", "2026-04-08 12:03:10"),
+            "2026-04-08 12:05:00"
+        );
+        assert_eq!(
+            next("*/5 * * * *", "2026-04-08 12:05:00"),
+            "2026-04-08 12:10:00"
+        );
+        assert_eq!(
+            next("30 9-17/4 * * *", "2026-04-08 13:31:00"),
+            "2026-04-08 17:30:00"
+        );
+        assert_eq!(
+            next("0 0 1 jan *", "2026-04-08 12:00:00"),
+            "2027-01-01 00:00:00"
+        );
+        assert_eq!(
+            next("@hourly", "2026-04-08 23:59:00"),
+            "2026-04-09 00:00:00"
+        );
+    }
+
+    #[test]
+    fn next_after_days_match_either_day_field() {
+        // 2026-04-08 is a Wednesday. The 10th or any Monday, whichever is first.
+        assert_eq!(
+            next("0 0 10 * mon", "2026-04-08 12:00:00"),
+            "2026-04-10 00:00:00"
+        );
+        assert_eq!(
+            next("0 0 10 * mon", "2026-04-10 12:00:00"),
+            "2026-04-13 00:00:00"
+        );
+
+        // With `*` in one of them, both have to match.
+        assert_eq!(
+            next("0 0 * * 7", "2026-04-08 12:00:00"),
+            "2026-04-12 00:00:00"
+        );
+    }
+
+    #[test]
+    fn next_after_gives_up_on_impossible_dates() {
+        let schedule = CronSchedule::parse("0 0 30 2 *").unwrap();
+        assert_eq!(schedule.next_after(time("2026-04-08 12:00:00")), None);
+    }
+
+    #[test]
+    fn parse_rejects_malformed_expressions() {
+        assert!(CronSchedule::parse("* * * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("5-1 * * * *").is_err());
+        assert!(CronSchedule::parse("* * * foo *").is_err());
+    }
+}
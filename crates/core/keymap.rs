@@ -38,7 +38,7 @@ pub struct Input {
     input: InputType,
 }
 
-const DEFAULT_KEYMAP: [&str; 48] = [
+const DEFAULT_KEYMAP: [&str; 52] = [
     "up=up",                                    // Up
     "down=down",                                // Down
     "pageup=page_up",                           // PageUp
@@ -71,6 +71,9 @@ const DEFAULT_KEYMAP: [&str; 48] = [
     "2=set_diff_mode_line",                     // Set Diff Mode Line: 2
     "3=set_diff_mode_word",                     // Set Diff Mode Word: 3
     "shift-o=set_diff_only",                    // Set Diff Only: Shift + o
+    "shift-b=set_diff_base",                    // Set Diff Base: Shift + b
+    "shift-c=clear_diff_base",                  // Clear Diff Base: Shift + c
+    "shift-p=pin_baseline",                     // Pin Baseline: Shift + p
     "o=toggle_output_mode",                     // Toggle Output Mode: o
     "w=toggle_wrap_mode",                       // Toggle Wrap Mode: w
     "f3=set_output_mode_output",                // Set Output Mode Output: F3
@@ -82,6 +85,7 @@ const DEFAULT_KEYMAP: [&str; 48] = [
     "plus=interval_plus",                       // Interval Plus: +
     "minus=interval_minus",                     // Interval Minus: -
     "p=toggle_pause",                           // Toggle Pause: p
+    "shift-r=run_now",                          // Run Now: Shift + r
     "/=change_filter_mode",                     // Change Filter Mode: /
     "*=change_regex_filter_mode",               // Change Regex Filter Mode: *
     "mouse-scroll_up=mouse_scroll_up",          // Mouse Scroll Up: Mouse Scroll Up
@@ -262,6 +266,12 @@ pub enum InputAction {
     SetDiffModeWord,
     #[serde(rename = "set_diff_only")]
     SetDiffOnly,
+    #[serde(rename = "set_diff_base")]
+    SetDiffBase,
+    #[serde(rename = "clear_diff_base")]
+    ClearDiffBase,
+    #[serde(rename = "pin_baseline")]
+    PinBaseline,
 
     // Output Mode
     // ==========
@@ -298,6 +308,8 @@ pub enum InputAction {
     IntervalMinus,
     #[serde(rename = "toggle_pause")]
     TogglePause,
+    #[serde(rename = "run_now")]
+    RunNow,
 
     // Command/Filter
     // ==========
@@ -57,6 +57,9 @@ pub fn get_input_action_description(input_action: InputAction) -> String {
         InputAction::SetDiffModeLine => "Set diff mode line".to_string(),
         InputAction::SetDiffModeWord => "Set diff mode word".to_string(),
         InputAction::SetDiffOnly => "Set diff line only (line/word diff only)".to_string(),
+        InputAction::SetDiffBase => "Diff against the selected history".to_string(),
+        InputAction::ClearDiffBase => "Clear the diff base".to_string(),
+        InputAction::PinBaseline => "Pin/unpin the selected history as baseline".to_string(),
         InputAction::ToggleOutputMode => "Toggle output mode".to_string(),
         InputAction::SetOutputModeOutput => "Set output mode output".to_string(),
         InputAction::SetOutputModeStdout => "Set output mode stdout".to_string(),
@@ -68,6 +71,7 @@ pub fn get_input_action_description(input_action: InputAction) -> String {
         InputAction::IntervalPlus => "Interval +0.5sec".to_string(),
         InputAction::IntervalMinus => "Interval -0.5sec".to_string(),
         InputAction::TogglePause => "Toggle Execution Pause".to_string(),
+        InputAction::RunNow => "Run the command now".to_string(),
         InputAction::ChangeFilterMode => "Change filter mode".to_string(),
         InputAction::ChangeRegexFilterMode => "Change regex filter mode".to_string(),
         InputAction::MouseScrollUp => "Mouse Scroll Up".to_string(),
@@ -38,7 +38,6 @@
 
 // v1.0.0
 // TODO(blacknon): vimのように内部コマンドを利用した表示切り替え・出力結果の編集機能を追加する
-// TODO(blacknon): 任意時点間のdiffが行えるようにする.
 // TODO(blacknon): filtering時に、`指定したキーワードで差分が発生した場合のみ`を対象にするような機能を追加する(command mode option)
 // TODO(blacknon): Rustのドキュメンテーションコメントを追加していく
 // TODO(blacknon): マニュアル(manのデータ)を自動作成させる
@@ -51,22 +50,25 @@ extern crate hwatch_diffmode;
 extern crate ratatui as tui;
 
 use clap::error::ErrorKind;
-use cli::{build_app, get_clap_matcher, should_continue_with_unreadable_logfile};
+use cli::{
+    build_app, get_clap_matcher, get_history_columns, should_continue_with_unreadable_logfile,
+};
 use common::load_logfile;
 use crossbeam_channel::unbounded;
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
 use diff_mode_registry::{calculate_diff_mode_header_width, register_diff_mode_name};
 use hwatch_diffmode::DiffMode;
-use interval::RunInterval;
+use interval::{Backoff, CronSchedule, RunInterval, ScheduleKind};
+use regex::Regex;
 use std::collections::HashMap;
 use std::io::IsTerminal;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, RwLock};
-use std::thread;
-use std::time::{Duration, SystemTime};
+use std::time::Duration;
 
 // local modules
 mod app;
+mod baseline;
 mod batch;
 mod cli;
 mod common;
@@ -78,6 +80,8 @@ mod diffmode_watch;
 mod errors;
 mod event;
 mod exec;
+mod follow;
+mod fswatch;
 mod header;
 mod help;
 mod history;
@@ -86,6 +90,9 @@ mod keymap;
 mod output;
 mod plugin_diffmode;
 mod popup;
+mod scheduler;
+mod snapshot;
+mod stable;
 mod view;
 mod watch;
 
@@ -95,6 +102,7 @@ pub const HISTORY_WIDTH: u16 = 25;
 pub const SHELL_COMMAND_EXECCMD: &str = "{COMMAND}";
 pub const HISTORY_LIMIT: &str = "5000";
 type SharedInterval = Arc<RwLock<RunInterval>>;
+type SharedPtySize = Arc<RwLock<exec::PtySize>>;
 
 // const at Windows
 #[cfg(windows)]
@@ -125,6 +133,43 @@ fn main() {
     let no_summary = matcher.get_flag("no_summary");
     let exit_on_change = matcher.get_one::<u32>("chgexit").copied();
 
+    // bounded runs
+    let count = matcher.get_one::<u64>("count").copied();
+    let deadline = matcher
+        .get_one::<Duration>("duration")
+        .map(|duration| std::time::Instant::now() + *duration);
+
+    // exit on the outcome of a run
+    let exit_on_status = match (
+        matcher.get_flag("until_success"),
+        matcher.get_flag("until_failure"),
+    ) {
+        (true, _) => Some(common::ExitOnStatus::Success),
+        (_, true) => Some(common::ExitOnStatus::Failure),
+        _ => None,
+    };
+    let exit_on_match = match (
+        matcher.get_one::<Regex>("until_match"),
+        matcher.get_one::<Regex>("until_no_match"),
+    ) {
+        (Some(regex), _) => Some(common::ExitOnMatch::new(regex.clone(), true)),
+        (_, Some(regex)) => Some(common::ExitOnMatch::new(regex.clone(), false)),
+        _ => None,
+    };
+    if exit_on_status.is_none() && exit_on_match.is_none() && matcher.get_flag("until_pause") {
+        let err = cmd_app.error(
+            ErrorKind::MissingRequiredArgument,
+            "--until-pause requires one of --until-success, --until-failure, --until-match or --until-no-match.".to_string(),
+        );
+        err.exit();
+    }
+
+    // set the exit code when hwatch stops on its own.
+    let is_bounded = count.is_some()
+        || deadline.is_some()
+        || exit_on_status.is_some()
+        || exit_on_match.is_some();
+
     // Get after command
     let after_command = matcher.get_one::<String>("after_command");
     let after_command_result_write_file = matcher.get_flag("after_command_result_write_file");
@@ -196,13 +241,219 @@ fn main() {
         }
     }
 
+    // Open a logfile read-only, without running anything.
+    let replay = matcher.get_one::<String>("replay");
+    if let Some(replay) = replay {
+        load_results = match load_logfile(replay, compress) {
+            Ok(results) => results,
+            Err(err) => {
+                let message = match err {
+                    common::LoadLogfileError::LogfileEmpty => "is empty".to_string(),
+                    common::LoadLogfileError::LoadFileError(err) => err.to_string(),
+                    common::LoadLogfileError::JsonParseError(err) => err.to_string(),
+                };
+                let err = cmd_app.error(
+                    ErrorKind::ValueValidation,
+                    format!("--replay {replay}: {message}"),
+                );
+                err.exit();
+            }
+        };
+    }
+
+    // Tail a logfile that another hwatch writes.
+    let follow_log = matcher.get_one::<String>("follow_log");
+    if let Some(follow_log) = follow_log {
+        if let Err(err) = std::fs::metadata(follow_log) {
+            let err = cmd_app.error(
+                ErrorKind::ValueValidation,
+                format!("--follow-log {follow_log}: {err}"),
+            );
+            err.exit();
+        }
+    }
+
+    // Compare every run with a fixed baseline.
+    let baseline = match matcher.get_one::<String>("baseline") {
+        Some(path) => match std::fs::read(path) {
+            Ok(text) => Some(baseline::Baseline::from_text(text)),
+            Err(err) => {
+                let err = cmd_app.error(
+                    ErrorKind::ValueValidation,
+                    format!("--baseline {path}: {err}"),
+                );
+                err.exit();
+            }
+        },
+        None if matcher.get_flag("baseline_first") => Some(baseline::Baseline::default()),
+        None => None,
+    };
+
+    // Assert every run against an expected file. With --update-expected,
+    // the file does not have to exist yet.
+    let update_expected = matcher.get_flag("update_expected");
+    let expect = match matcher.get_one::<String>("expect") {
+        Some(path) => match std::fs::read(path) {
+            Ok(text) => Some((PathBuf::from(path), text)),
+            Err(err) if update_expected && err.kind() == std::io::ErrorKind::NotFound => {
+                Some((PathBuf::from(path), vec![]))
+            }
+            Err(err) => {
+                let err = cmd_app.error(
+                    ErrorKind::ValueValidation,
+                    format!("--expect {path}: {err}"),
+                );
+                err.exit();
+            }
+        },
+        None => None,
+    };
+    let is_bounded = is_bounded || expect.is_some();
+
     // Create channel
     let (tx, rx) = unbounded();
 
     // interval
-    let shared_interval: SharedInterval = match matcher.get_one::<f64>("interval") {
-        Some(override_interval) => SharedInterval::new(RunInterval::new(*override_interval).into()),
-        None => SharedInterval::default(),
+    let mut run_interval = match matcher.get_one::<f64>("interval") {
+        Some(override_interval) => RunInterval::new(*override_interval),
+        None => RunInterval::default(),
+    };
+    if let Some(schedule) = matcher.get_one::<CronSchedule>("cron") {
+        run_interval.set_kind(ScheduleKind::Cron(schedule.clone()));
+        if run_interval.next_scheduled(chrono::Local::now()).is_none() {
+            let err = cmd_app.error(
+                ErrorKind::ValueValidation,
+                format!("--cron '{}' never matches.", schedule.expression()),
+            );
+            err.exit();
+        }
+    } else if matcher.get_flag("align") {
+        // Slots counted from midnight need a positive length.
+        if !(run_interval.interval.is_finite() && run_interval.interval > 0.0) {
+            let err = cmd_app.error(
+                ErrorKind::ValueValidation,
+                "--align needs an interval greater than 0.".to_string(),
+            );
+            err.exit();
+        }
+        run_interval.set_kind(ScheduleKind::Align);
+    }
+
+    // backoff
+    if let Some(max) = matcher.get_one::<f64>("backoff_max") {
+        let factor = *matcher.get_one::<f64>("backoff_factor").unwrap_or(&2.0);
+        let valid = max.is_finite() && *max > 0.0 && factor.is_finite() && factor >= 1.0;
+        if !valid {
+            let err = cmd_app.error(
+                ErrorKind::ValueValidation,
+                "--backoff-max must be a positive number of seconds and --backoff-factor at least 1."
+                    .to_string(),
+            );
+            err.exit();
+        }
+
+        let unchanged_after = matcher.get_one::<u32>("backoff_unchanged").copied();
+        run_interval.set_backoff(Backoff::new(factor, *max).set_unchanged_after(unchanged_after));
+    }
+
+    let shared_interval: SharedInterval = SharedInterval::new(run_interval.into());
+
+    // command timeout
+    let timeout = match matcher.get_one::<f64>("timeout") {
+        Some(seconds) if seconds.is_finite() && *seconds > 0.0 => {
+            Some(Duration::from_secs_f64(*seconds))
+        }
+        Some(_) => {
+            let err = cmd_app.error(
+                ErrorKind::ValueValidation,
+                "--timeout must be a positive number of seconds.".to_string(),
+            );
+            err.exit();
+        }
+        None => None,
+    };
+
+    // working directory and environment of the command
+    let cwd = matcher.get_one::<String>("cwd").map(PathBuf::from);
+    if let Some(cwd) = &cwd {
+        if !cwd.is_dir() {
+            let err = cmd_app.error(
+                ErrorKind::ValueValidation,
+                format!("--cwd {cwd:?} is not a directory."),
+            );
+            err.exit();
+        }
+    }
+
+    let mut command_env = vec![];
+    for env_file in matcher.get_many::<String>("env_file").unwrap_or_default() {
+        let env = std::fs::read_to_string(env_file)
+            .map_err(|err| err.to_string())
+            .and_then(|text| common::parse_env_file(&text));
+        match env {
+            Ok(env) => command_env.extend(env),
+            Err(err) => {
+                let err = cmd_app.error(
+                    ErrorKind::ValueValidation,
+                    format!("failed to read --env-file {env_file:?}: {err}"),
+                );
+                err.exit();
+            }
+        }
+    }
+    command_env.extend(
+        matcher
+            .get_many::<(String, String)>("env")
+            .unwrap_or_default()
+            .cloned(),
+    );
+
+    // pty size. Follows the watch pane in the TUI unless --pty-size is given.
+    let fixed_pty_size = matcher.get_one::<exec::PtySize>("pty_size").copied();
+    let shared_pty_size: SharedPtySize = Arc::new(RwLock::new(fixed_pty_size.unwrap_or_else(
+        || match crossterm::terminal::size() {
+            Ok((cols, rows)) if cols > 0 && rows > 0 => exec::PtySize { cols, rows },
+            _ => exec::PtySize::default(),
+        },
+    )));
+
+    // overlap policy
+    let overlap = matcher
+        .get_one::<String>("overlap")
+        .and_then(|name| scheduler::OverlapPolicy::from_name(name))
+        .unwrap_or(scheduler::OverlapPolicy::Queue);
+
+    // watched paths
+    let watch_paths: Vec<String> = matcher
+        .get_many::<String>("watch_path")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let watch_path_only = matcher.get_flag("watch_path_only");
+
+    // data read instead of running a command
+    let source_file = matcher.get_one::<String>("file").cloned();
+    let stdin_snapshots = matcher.get_flag("stdin_snapshots");
+
+    // --file is also read as soon as it is rewritten.
+    let mut watch_globs = watch_paths.clone();
+    if let Some(file) = &source_file {
+        watch_globs.push(glob::Pattern::escape(file));
+    }
+    let changes = match watch_globs.is_empty() {
+        true => None,
+        false => match fswatch::watch_paths(&watch_globs) {
+            Ok(changes) => Some(changes),
+            // Without --watch-path, --file is read on the interval only.
+            Err(_) if watch_paths.is_empty() => None,
+            Err(message) => {
+                let err = cmd_app.error(
+                    ErrorKind::ValueValidation,
+                    format!("--watch-path {message}"),
+                );
+                err.exit();
+            }
+        },
     };
 
     // history limit
@@ -225,6 +476,15 @@ fn main() {
         _ => common::OutputMode::Output,
     };
 
+    // history pane columns
+    let history_columns = get_history_columns(&matcher);
+
+    let stable_for = matcher.get_one::<u32>("stable_for").copied();
+    let stdin_file = matcher.get_one::<String>("stdin_file").map(PathBuf::from);
+    let max_output_bytes = matcher
+        .get_one::<u64>("max_output_bytes")
+        .map(|bytes| usize::try_from(*bytes).unwrap_or(usize::MAX));
+
     // Get Add keymap
     let keymap_options: Vec<&str> = matcher
         .get_many::<String>("keymap")
@@ -273,6 +533,8 @@ fn main() {
     let command_line: Vec<String>;
     if let Some(value) = matcher.get_many::<String>("command") {
         command_line = value.into_iter().cloned().collect();
+    } else if source_file.is_some() || stdin_snapshots || replay.is_some() || follow_log.is_some() {
+        command_line = vec![];
     } else {
         // check load_results
         if load_results.is_empty() {
@@ -297,57 +559,71 @@ fn main() {
         };
     }
 
+    // run now, from the keymap or SIGUSR1
+    let (run_now_tx, run_now_rx) = unbounded();
+    #[cfg(unix)]
+    if let Err(err) = scheduler::run_now_on_sigusr1(run_now_tx.clone()) {
+        eprintln!("Failed to listen for SIGUSR1: {err}");
+    }
+
     // Start Command Thread
-    {
-        let m = matcher.clone();
-        let tx = tx.clone();
-        let shell_command = shell_command.clone();
-        let command: Vec<_> = command_line;
-        let is_exec = m.get_flag("exec");
-        let is_pty = m.get_flag("use_pty");
-        let run_interval_ptr = shared_interval.clone();
-        let _ = thread::spawn(move || loop {
-            let run_interval = run_interval_ptr.read().expect("Non poisoned block");
-            let paused = run_interval.paused;
-            let interval = run_interval.interval;
-            drop(run_interval); // We manually drop here or else it locks anything else from reading/writing the interval
-            let mut time_to_sleep: f64 = interval;
-
-            if !paused {
-                // Create cmd..
-                let mut exe = exec::ExecuteCommand::new(tx.clone());
-
-                // Set shell command
-                exe.shell_command = shell_command.clone();
-
-                // Set command
-                exe.command = command.clone();
-
-                // Set compress
-                exe.is_compress = compress;
-
-                // Set is exec flag.
-                exe.is_exec = is_exec;
-                exe.is_pty = is_pty;
-
-                let before_start = SystemTime::now();
-                // Exec command
-                exe.exec_command();
-
-                if precise {
-                    let elapsed: f64 = SystemTime::now()
-                        .duration_since(before_start)
-                        .unwrap_or_default()
-                        .as_secs_f64();
-                    time_to_sleep = match elapsed > time_to_sleep {
-                        true => 0_f64,
-                        false => time_to_sleep - elapsed,
-                    };
-                }
-            }
+    if replay.is_some() {
+        // Nothing runs: every result comes from the logfile.
+    } else if let Some(follow_log) = follow_log {
+        let _ = follow::LogFollower::new(PathBuf::from(follow_log), tx.clone())
+            .set_compress(compress)
+            .spawn();
+    } else if stdin_snapshots {
+        let delimiter = matcher.get_one::<String>("snapshot_delimiter").cloned();
+        let idle = match (matcher.get_one::<Duration>("snapshot_idle"), &delimiter) {
+            (Some(idle), _) => Some(*idle),
+            (None, Some(_)) => None,
+            (None, None) => Some(snapshot::DEFAULT_SNAPSHOT_IDLE),
+        };
 
-            std::thread::sleep(Duration::from_secs_f64(time_to_sleep));
-        });
+        let _ = snapshot::StdinSnapshots::new(tx.clone())
+            .set_delimiter(delimiter)
+            .set_idle(idle)
+            .set_compress(compress)
+            .set_exit_on_eof(batch)
+            .spawn(std::io::stdin());
+    } else {
+        // Create cmd..
+        let mut exe = exec::ExecuteCommand::new(tx.clone());
+
+        // Set shell command
+        exe.shell_command = shell_command.clone();
+
+        // Set command
+        exe.command = command_line;
+
+        // Set compress
+        exe.is_compress = compress;
+
+        // Set is exec flag.
+        exe.is_exec = matcher.get_flag("exec");
+        exe.is_pty = matcher.get_flag("use_pty");
+        exe.pty_size = shared_pty_size.clone();
+        exe.timeout = timeout;
+        exe.cwd = cwd;
+        exe.env = command_env;
+        exe.stdin_file = stdin_file;
+        exe.file = source_file.map(PathBuf::from);
+        exe.max_output_bytes = max_output_bytes;
+        exe.retries = matcher.get_one::<u32>("retries").copied().unwrap_or(0);
+        exe.retry_delay = *matcher.get_one::<Duration>("retry_delay").unwrap();
+
+        let mut scheduler = scheduler::Scheduler::new(exe, shared_interval.clone())
+            .set_precise(precise)
+            .set_overlap(overlap)
+            .set_streaming(!batch)
+            .set_run_now(run_now_rx)
+            .set_count(count)
+            .set_deadline(deadline);
+        if let Some(changes) = changes {
+            scheduler = scheduler.set_changes(changes, !watch_path_only);
+        }
+        let _ = scheduler.spawn();
     }
 
     let mut diff_mode_name_to_index: HashMap<String, usize> = HashMap::new();
@@ -443,12 +719,19 @@ fn main() {
         // Create view
         let mut view = view::View::new(shared_interval.clone(), diff_modes)
             .set_tab_size(tab_size)
-            .set_limit(*limit)
+            // A replay shows the whole logfile.
+            .set_limit(if replay.is_some() { 0 } else { *limit })
             .set_beep(matcher.get_flag("beep"))
             .set_exit_on_change(exit_on_change)
+            .set_exit_on_status(exit_on_status)
+            .set_exit_on_match(exit_on_match.clone())
+            .set_stable_for(stable_for)
+            .set_baseline(baseline.clone())
+            .set_pause_on_exit(matcher.get_flag("until_pause"))
             .set_border(matcher.get_flag("border"))
             .set_scroll_bar(matcher.get_flag("with_scrollbar"))
             .set_mouse_events(matcher.get_flag("mouse"))
+            .set_run_now(run_now_tx)
             // set keymap
             .set_keymap(keymap)
             // Set color in view
@@ -471,12 +754,27 @@ fn main() {
             .set_no_summary(no_summary)
             // Set enable summary char
             .set_enable_summary_char(enable_summary_char)
+            .set_history_columns(history_columns)
             .set_show_ui(!matcher.get_flag("no_title"))
             .set_show_help_banner(!matcher.get_flag("no_help_banner"));
 
         // Set logfile
         if let Some(logfile) = logfile {
-            view = view.set_logfile(logfile.to_string());
+            view = view
+                .set_logfile(logfile.to_string())
+                .set_log_heartbeat(matcher.get_flag("log_heartbeat"));
+        }
+
+        let log_source = match (replay, follow_log) {
+            (Some(replay), _) => Some(header::LogSource::Replay(replay.clone())),
+            (_, Some(follow_log)) => Some(header::LogSource::Follow(follow_log.clone())),
+            _ => None,
+        };
+        view = view.set_log_source(log_source);
+
+        // Resize the pty along with the watch pane
+        if fixed_pty_size.is_none() {
+            view = view.set_pty_size(shared_pty_size);
         }
 
         // Set after_command
@@ -487,13 +785,22 @@ fn main() {
         }
 
         // start app.
-        let _res = view.start(tx, rx, load_results);
+        let res = view.start(tx, rx, load_results);
+        if let (true, Ok(code)) = (is_bounded, res) {
+            std::process::exit(code);
+        }
     } else {
         // is batch mode
         let mut batch = batch::Batch::new(rx, diff_modes)
             .set_beep(matcher.get_flag("beep"))
             .set_color(matcher.get_flag("color"))
             .set_exit_on_change(exit_on_change)
+            .set_exit_on_status(exit_on_status)
+            .set_exit_on_match(exit_on_match)
+            .set_stable_for(stable_for)
+            .set_baseline(baseline)
+            .set_expect(expect)
+            .set_update_expected(update_expected)
             .set_output_mode(output_mode)
             .set_diff_mode(diff_mode)
             .set_line_number(matcher.get_flag("line_number"))
@@ -503,7 +810,9 @@ fn main() {
 
         // Set logfile
         if let Some(logfile) = logfile {
-            batch = batch.set_logfile(logfile.to_string());
+            batch = batch
+                .set_logfile(logfile.to_string())
+                .set_log_heartbeat(matcher.get_flag("log_heartbeat"));
         }
 
         // Set after_command
@@ -514,6 +823,9 @@ fn main() {
         }
 
         // start batch.
-        let _res = batch.run();
+        let res = batch.run();
+        if let (true, Ok(code)) = (is_bounded, res) {
+            std::process::exit(code);
+        }
     }
 }
@@ -12,11 +12,15 @@
 // use std::fmt::Write;
 use std::sync::{Arc, Mutex};
 use tui::prelude::Line;
-use tui::style::Color;
+use tui::style::{Color, Style};
 
 // local const
 use crate::DEFAULT_TAB_SIZE;
 
+/// Line shown after the output of a result cut at `--max-output-bytes`.
+/// It is added after the diff, so it never shows up as a change itself.
+const TRUNCATED_MARKER: &str = "hwatch: output truncated (--max-output-bytes)";
+
 // local module
 use crate::common::OutputMode;
 use crate::exec::CommandResult;
@@ -92,7 +96,13 @@ impl Printer {
         let is_line_diff_head = diff_mode.get_support_only_diffline();
 
         // create diff
-        let result = diff_mode.generate_watch_diff(&text_dest, &text_src);
+        let mut result = diff_mode.generate_watch_diff(&text_dest, &text_src);
+        if dest.truncated {
+            result.push(Line::styled(
+                TRUNCATED_MARKER,
+                Style::default().fg(Color::Yellow),
+            ));
+        }
 
         let lines = maybe_reverse_lines(result, self.is_reverse);
 
@@ -114,7 +124,10 @@ impl Printer {
         diff_mode.set_option(self.options);
 
         // create diff
-        let result = diff_mode.generate_batch_diff(&text_dest, &text_src);
+        let mut result = diff_mode.generate_batch_diff(&text_dest, &text_src);
+        if dest.truncated {
+            result.push(TRUNCATED_MARKER.to_string());
+        }
 
         maybe_reverse_strings(result, self.is_reverse)
     }
@@ -17,6 +17,7 @@ pub struct PopupWindow<'a> {
     title: String,
     text: Vec<Line<'a>>,
     area: Rect,
+    reversed: bool,
 }
 
 impl<'a> PopupWindow<'a> {
@@ -30,9 +31,16 @@ impl<'a> PopupWindow<'a> {
             title: title.into(),
             text,
             area: Rect::new(0, 0, 0, 0),
+            reversed: false,
         }
     }
 
+    /// draw with inverted colors, to flash the popup
+    pub fn set_reversed(mut self, reversed: bool) -> Self {
+        self.reversed = reversed;
+        self
+    }
+
     /// draw popup window
     pub fn draw(&mut self, f: &mut Frame) {
         // title string
@@ -58,8 +66,13 @@ impl<'a> PopupWindow<'a> {
         self.area = centered_rect_with_size(total_h, total_w, max);
 
         // ---- 4) 描画 ----
+        let mut style = Style::default().bold();
+        if self.reversed {
+            style = style.reversed();
+        }
+
         let block = Paragraph::new(self.text.clone())
-            .style(Style::default().bold())
+            .style(style)
             .block(
                 Block::default()
                     .title(title_str)
@@ -0,0 +1,838 @@
+// Copyright (c) 2026 Blacknon. All rights reserved.
+// Use of this source code is governed by an MIT license
+// that can be found in the LICENSE file.
+
+// module
+use chrono::{Local, TimeDelta};
+use crossbeam_channel::{Receiver, Select, Sender};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// local module
+use crate::common::exit_code;
+use crate::event::AppEvent;
+use crate::exec::{CommandResult, ExecuteCommand};
+use crate::fswatch;
+use crate::interval::ScheduleKind;
+use crate::SharedInterval;
+
+/// What to do when the next run is due while the previous one is still running.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// Do not start a new run for this tick.
+    Skip,
+    /// Wait for the running command, then start the next one.
+    Queue,
+    /// Kill the running command and start the next one. The killed run is not recorded.
+    Kill,
+    /// Start the next run alongside the running ones.
+    Parallel,
+}
+
+impl OverlapPolicy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "skip" => Some(OverlapPolicy::Skip),
+            "queue" => Some(OverlapPolicy::Queue),
+            "kill" => Some(OverlapPolicy::Kill),
+            "parallel" => Some(OverlapPolicy::Parallel),
+            _ => None,
+        }
+    }
+}
+
+struct InFlight {
+    cancel: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+/// Passes results on in the order their runs were started, even when a later
+/// run finishes first. The backoff policy sees them in the same order.
+struct ResultOrder {
+    next: u64,
+    pending: BTreeMap<u64, Option<CommandResult>>,
+    interval: SharedInterval,
+    tx: Sender<AppEvent>,
+    /// exit code of the last result passed on, for `HWATCH_PREV_STATUS`.
+    prev_status: Option<i32>,
+    /// holds the output of the last result, for `HWATCH_PREV_OUTPUT_FILE`.
+    prev_output_dir: Option<tempfile::TempDir>,
+}
+
+impl ResultOrder {
+    fn new(tx: Sender<AppEvent>, interval: SharedInterval) -> Self {
+        // The file exists from the start, so the first run can read it as empty.
+        let prev_output_dir = tempfile::Builder::new()
+            .prefix("hwatch-")
+            .tempdir()
+            .ok()
+            .filter(|dir| std::fs::write(dir.path().join(PREV_OUTPUT_FILE), "").is_ok());
+
+        Self {
+            next: 0,
+            pending: BTreeMap::new(),
+            interval,
+            tx,
+            prev_status: None,
+            prev_output_dir,
+        }
+    }
+
+    fn prev_output_path(&self) -> Option<PathBuf> {
+        let dir = self.prev_output_dir.as_ref()?;
+        Some(dir.path().join(PREV_OUTPUT_FILE))
+    }
+
+    /// Replace the previous output file in one step, so a run reading it
+    /// never sees it half written.
+    fn save_prev(&mut self, result: &CommandResult) {
+        self.prev_status = Some(exit_code(result));
+
+        if let Some(dir) = &self.prev_output_dir {
+            let _ = write_replace(dir.path(), PREV_OUTPUT_FILE, result.get_output().as_bytes());
+        }
+    }
+
+    /// `None` marks a run that was cancelled and only releases the runs after it.
+    fn push(&mut self, seq: u64, result: Option<CommandResult>) {
+        self.pending.insert(seq, result);
+
+        while let Some(result) = self.pending.remove(&self.next) {
+            if let Some(result) = result {
+                self.interval.write().unwrap().record_result(&result);
+                self.save_prev(&result);
+                let _ = self.tx.send(AppEvent::OutputUpdate(Box::new(result)));
+            }
+            self.next += 1;
+        }
+    }
+}
+
+const PREV_OUTPUT_FILE: &str = "prev_output";
+
+fn write_replace(dir: &Path, name: &str, data: &[u8]) -> std::io::Result<()> {
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(data)?;
+    file.persist(dir.join(name))?;
+    Ok(())
+}
+
+/// Request a run whenever hwatch receives SIGUSR1.
+#[cfg(unix)]
+pub fn run_now_on_sigusr1(run_now: Sender<()>) -> std::io::Result<()> {
+    let mut signals = signal_hook::iterator::Signals::new([signal_hook::consts::SIGUSR1])?;
+    thread::spawn(move || {
+        for _ in signals.forever() {
+            if run_now.send(()).is_err() {
+                return;
+            }
+        }
+    });
+    Ok(())
+}
+
+/// What ended `Scheduler::wait` early. `None` means the channel was closed.
+enum Wake {
+    Change(Option<PathBuf>),
+    RunNow(Option<()>),
+}
+
+/// Runs the command on the interval.
+pub struct Scheduler {
+    command: ExecuteCommand,
+    interval: SharedInterval,
+    precise: bool,
+    overlap: OverlapPolicy,
+    in_flight: Vec<InFlight>,
+    order: Arc<Mutex<ResultOrder>>,
+    seq: u64,
+    streaming: bool,
+    changes: Option<Receiver<PathBuf>>,
+    interval_fallback: bool,
+    trigger: Option<PathBuf>,
+    run_now: Option<Receiver<()>>,
+    run_requested: bool,
+    count: Option<u64>,
+    deadline: Option<Instant>,
+}
+
+impl Scheduler {
+    pub fn new(command: ExecuteCommand, interval: SharedInterval) -> Self {
+        let order = ResultOrder::new(command.tx.clone(), interval.clone());
+
+        Self {
+            command,
+            interval,
+            precise: false,
+            overlap: OverlapPolicy::Queue,
+            in_flight: vec![],
+            order: Arc::new(Mutex::new(order)),
+            seq: 0,
+            streaming: false,
+            changes: None,
+            interval_fallback: true,
+            trigger: None,
+            run_now: None,
+            run_requested: false,
+            count: None,
+            deadline: None,
+        }
+    }
+
+    pub fn set_precise(mut self, precise: bool) -> Self {
+        self.precise = precise;
+        self
+    }
+
+    pub fn set_overlap(mut self, overlap: OverlapPolicy) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// Send the output of running commands as `AppEvent::OutputChunk`.
+    pub fn set_streaming(mut self, streaming: bool) -> Self {
+        self.streaming = streaming;
+        self
+    }
+
+    /// Also start a run when a path arrives on `changes`. Without
+    /// `interval_fallback` the interval no longer starts runs by itself.
+    pub fn set_changes(mut self, changes: Receiver<PathBuf>, interval_fallback: bool) -> Self {
+        self.changes = Some(changes);
+        self.interval_fallback = interval_fallback;
+        self
+    }
+
+    /// Start a run as soon as `()` arrives on `run_now`, even while paused.
+    pub fn set_run_now(mut self, run_now: Receiver<()>) -> Self {
+        self.run_now = Some(run_now);
+        self
+    }
+
+    /// Stop after this many runs.
+    pub fn set_count(mut self, count: Option<u64>) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Do not start runs after this point.
+    pub fn set_deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Runs until `--count` or `--duration` is used up, then sends `AppEvent::Exit`.
+    pub fn spawn(mut self) -> JoinHandle<()> {
+        thread::spawn(move || {
+            while self.has_runs_left() {
+                let time_to_sleep = self.tick();
+                if self.has_runs_left() {
+                    self.wait(time_to_sleep);
+                }
+            }
+            self.finish();
+        })
+    }
+
+    fn has_runs_left(&self) -> bool {
+        let counted_out = matches!(self.count, Some(count) if self.seq >= count);
+        let timed_out = matches!(self.deadline, Some(deadline) if Instant::now() >= deadline);
+        !counted_out && !timed_out
+    }
+
+    /// Let the runs still going deliver their results, then close the app.
+    fn finish(&mut self) {
+        for run in self.in_flight.drain(..) {
+            let _ = run.handle.join();
+        }
+        let _ = self.command.tx.send(AppEvent::Exit);
+    }
+
+    /// Sleep until the next tick, until a watched file changes or until a run
+    /// is requested.
+    fn wait(&mut self, seconds: f64) {
+        let deadline = match self.changes.is_some() && !self.interval_fallback {
+            true => None,
+            false => Some(Instant::now() + Duration::from_secs_f64(seconds)),
+        };
+        // Wake up for --duration even when nothing else is due.
+        let deadline = match (deadline, self.deadline) {
+            (Some(next), Some(limit)) => Some(next.min(limit)),
+            (next, limit) => next.or(limit),
+        };
+
+        loop {
+            if self.changes.is_none() && self.run_now.is_none() {
+                match deadline {
+                    Some(deadline) => {
+                        thread::sleep(deadline.saturating_duration_since(Instant::now()))
+                    }
+                    None => loop {
+                        thread::park();
+                    },
+                }
+                return;
+            }
+
+            let wake = {
+                let mut select = Select::new();
+                let changes = self.changes.as_ref().map(|rx| select.recv(rx));
+                let run_now = self.run_now.as_ref().map(|rx| select.recv(rx));
+
+                let operation = match deadline {
+                    Some(deadline) => match select.select_deadline(deadline) {
+                        Ok(operation) => operation,
+                        Err(_) => return,
+                    },
+                    None => select.select(),
+                };
+
+                if Some(operation.index()) == changes {
+                    let rx = self.changes.as_ref().unwrap();
+                    Wake::Change(operation.recv(rx).ok())
+                } else {
+                    debug_assert_eq!(Some(operation.index()), run_now);
+                    let rx = self.run_now.as_ref().unwrap();
+                    Wake::RunNow(operation.recv(rx).ok())
+                }
+            };
+
+            // A closed channel is dropped so the others can still be waited on.
+            match wake {
+                Wake::Change(Some(path)) => {
+                    let changes = self.changes.as_ref().unwrap();
+                    self.trigger = Some(fswatch::debounce(changes, path));
+                    return;
+                }
+                Wake::Change(None) => self.changes = None,
+                Wake::RunNow(Some(())) => {
+                    self.run_requested = true;
+                    return;
+                }
+                Wake::RunNow(None) => self.run_now = None,
+            }
+        }
+    }
+
+    /// Start the run that is due now. Returns the seconds to wait for the next tick.
+    fn tick(&mut self) -> f64 {
+        let paused = self.interval.read().expect("Non poisoned block").paused;
+
+        let requested = std::mem::take(&mut self.run_requested);
+        if paused && !requested {
+            self.trigger = None;
+            return self.schedule_next(None);
+        }
+
+        let before_start = Instant::now();
+        self.in_flight.retain(|run| !run.handle.is_finished());
+
+        // Without --precise the next tick is only counted after the run ends,
+        // so runs can never overlap.
+        let overlap = match self.precise {
+            true => self.overlap,
+            false => OverlapPolicy::Queue,
+        };
+
+        match overlap {
+            OverlapPolicy::Queue => self.run_inline(),
+            OverlapPolicy::Skip => {
+                if self.in_flight.is_empty() {
+                    self.run_background();
+                }
+            }
+            OverlapPolicy::Kill => {
+                for run in &self.in_flight {
+                    run.cancel.store(true, Ordering::Relaxed);
+                }
+                self.run_background();
+            }
+            OverlapPolicy::Parallel => self.run_background(),
+        }
+
+        self.schedule_next(Some(before_start))
+    }
+
+    /// Seconds until the next run, which is also published to the header as
+    /// `RunInterval::next_run`. `started` is when the run of this tick began.
+    fn schedule_next(&self, started: Option<Instant>) -> f64 {
+        let mut run_interval = self.interval.write().expect("Non poisoned block");
+        let now = Local::now();
+
+        let wait = match run_interval.next_scheduled(now) {
+            Some(next) => (next - now).num_milliseconds().max(0) as f64 / 1000.0,
+            None => match (self.precise, started) {
+                (true, Some(started)) => {
+                    (run_interval.effective_interval() - started.elapsed().as_secs_f64()).max(0.0)
+                }
+                _ => run_interval.effective_interval(),
+            },
+        };
+
+        let by_interval = self.changes.is_none() || self.interval_fallback;
+        run_interval.next_run = match !run_interval.paused && by_interval {
+            true => Some(now + TimeDelta::milliseconds((wait * 1000.0) as i64)),
+            false => None,
+        };
+        drop(run_interval); // Release the lock before the app reads it for the redraw.
+
+        let _ = self.command.tx.send(AppEvent::Redraw);
+        wait
+    }
+
+    fn next_seq(&mut self) -> u64 {
+        let seq = self.seq;
+        self.seq += 1;
+        seq
+    }
+
+    /// Prepare the command for the next run.
+    fn start_run(&mut self) -> (u64, ExecuteCommand) {
+        let seq = self.next_seq();
+        let mut command = self.command.clone();
+        if let Some(trigger) = self.trigger.take() {
+            command.trigger = trigger.display().to_string();
+        }
+        command.env.extend(self.run_env(seq));
+
+        if self.streaming {
+            let tx = command.tx.clone();
+            let _ = tx.send(AppEvent::RunStarted(seq));
+            command.on_output = Some(Arc::new(move |mode, chunk: &[u8]| {
+                let _ = tx.send(AppEvent::OutputChunk(seq, mode, chunk.to_vec()));
+            }));
+            let tx = command.tx.clone();
+            command.on_retry = Some(Arc::new(move |attempt, attempts| {
+                let _ = tx.send(AppEvent::RunRetrying(seq, attempt, attempts));
+            }));
+        }
+
+        (seq, command)
+    }
+
+    /// Variables that tell the command about this run and the one before it.
+    /// The previous ones are empty on the first run.
+    fn run_env(&self, seq: u64) -> Vec<(String, String)> {
+        let order = self.order.lock().unwrap();
+        let prev_status = order.prev_status.map(|code| code.to_string());
+        let prev_output = order.prev_output_path();
+        drop(order);
+
+        let run_interval = self.interval.read().unwrap();
+        let interval = match run_interval.kind {
+            // Cron slots do not follow the interval.
+            ScheduleKind::Cron(_) => None,
+            _ => Some(run_interval.effective_interval()),
+        };
+        drop(run_interval);
+
+        let mut env = vec![
+            ("HWATCH_RUN".to_string(), (seq + 1).to_string()),
+            (
+                "HWATCH_PREV_STATUS".to_string(),
+                prev_status.unwrap_or_default(),
+            ),
+            (
+                "HWATCH_PREV_OUTPUT_FILE".to_string(),
+                prev_output
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default(),
+            ),
+        ];
+        if let Some(interval) = interval {
+            env.push(("HWATCH_INTERVAL".to_string(), interval.to_string()));
+        }
+        env
+    }
+
+    fn finish_run(
+        order: &Mutex<ResultOrder>,
+        seq: u64,
+        result: Option<CommandResult>,
+        streaming: bool,
+    ) {
+        let mut order = order.lock().unwrap();
+        order.push(seq, result);
+        if streaming {
+            let _ = order.tx.send(AppEvent::RunFinished(seq));
+        }
+    }
+
+    fn run_inline(&mut self) {
+        let (seq, mut command) = self.start_run();
+        let result = command.exec();
+        Self::finish_run(&self.order, seq, result, self.streaming);
+    }
+
+    fn run_background(&mut self) {
+        let (seq, mut command) = self.start_run();
+        let cancel = Arc::new(AtomicBool::new(false));
+        command.cancel = Some(cancel.clone());
+
+        let order = self.order.clone();
+        let streaming = self.streaming;
+        let handle = thread::spawn(move || {
+            let result = command.exec();
+            Self::finish_run(&order, seq, result, streaming);
+        });
+
+        self.in_flight.push(InFlight { cancel, handle });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interval::ScheduleKind;
+    use crossbeam_channel::unbounded;
+
+    fn result(command: &str) -> CommandResult {
+        CommandResult {
+            command: command.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn received_commands(rx: &crossbeam_channel::Receiver<AppEvent>) -> Vec<String> {
+        rx.try_iter()
+            .filter_map(|event| match event {
+                AppEvent::OutputUpdate(result) => Some(result.command),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn result_order_holds_results_until_earlier_runs_finish() {
+        let (tx, rx) = unbounded();
+        let mut order = ResultOrder::new(tx, SharedInterval::default());
+
+        order.push(1, Some(result("second")));
+        order.push(2, Some(result("third")));
+        assert!(received_commands(&rx).is_empty());
+
+        order.push(0, Some(result("first")));
+        assert_eq!(received_commands(&rx), vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    fn result_order_skips_cancelled_runs() {
+        let (tx, rx) = unbounded();
+        let mut order = ResultOrder::new(tx, SharedInterval::default());
+
+        order.push(1, Some(result("second")));
+        order.push(0, None);
+
+        assert_eq!(received_commands(&rx), vec!["second"]);
+    }
+
+    #[cfg(unix)]
+    fn sleeping_scheduler(
+        overlap: OverlapPolicy,
+    ) -> (Scheduler, crossbeam_channel::Receiver<AppEvent>) {
+        let (tx, rx) = unbounded();
+        let mut command = ExecuteCommand::new(tx);
+        command.shell_command = "sh -c".to_string();
+        command.command = vec!["sleep 0.5; echo done".to_string()];
+
+        let interval = SharedInterval::new(crate::RunInterval::new(0.1).into());
+        let scheduler = Scheduler::new(command, interval)
+            .set_precise(true)
+            .set_overlap(overlap);
+        (scheduler, rx)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn skip_does_not_start_a_run_while_one_is_in_flight() {
+        let (mut scheduler, rx) = sleeping_scheduler(OverlapPolicy::Skip);
+
+        scheduler.tick();
+        scheduler.tick();
+        assert_eq!(scheduler.seq, 1);
+
+        let event = std::iter::from_fn(|| rx.recv_timeout(Duration::from_secs(5)).ok())
+            .find(|event| !matches!(event, AppEvent::Redraw));
+        assert!(matches!(event, Some(AppEvent::OutputUpdate(_))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn kill_drops_the_previous_run() {
+        let (mut scheduler, rx) = sleeping_scheduler(OverlapPolicy::Kill);
+
+        scheduler.tick();
+        scheduler.tick();
+        assert_eq!(scheduler.seq, 2);
+
+        for run in scheduler.in_flight.drain(..) {
+            run.handle.join().unwrap();
+        }
+        assert_eq!(received_commands(&rx).len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn streaming_sends_start_chunks_result_and_finish_in_order() {
+        let (tx, rx) = unbounded();
+        let mut command = ExecuteCommand::new(tx);
+        command.shell_command = "sh -c".to_string();
+        command.command = vec!["printf hello".to_string()];
+
+        let interval = SharedInterval::new(crate::RunInterval::new(60.0).into());
+        let mut scheduler = Scheduler::new(command, interval).set_streaming(true);
+        scheduler.tick();
+
+        let events: Vec<String> = rx
+            .try_iter()
+            .filter_map(|event| match event {
+                AppEvent::RunStarted(seq) => Some(format!("started {seq}")),
+                AppEvent::OutputChunk(seq, _, chunk) => {
+                    Some(format!("chunk {seq} {}", String::from_utf8_lossy(&chunk)))
+                }
+                AppEvent::OutputUpdate(result) => Some(format!("result {}", result.get_output())),
+                AppEvent::RunFinished(seq) => Some(format!("finished {seq}")),
+                AppEvent::Redraw => None,
+                _ => Some("other".to_string()),
+            })
+            .collect();
+
+        assert_eq!(
+            events,
+            vec!["started 0", "chunk 0 hello", "result hello", "finished 0"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn trigger_is_recorded_on_the_next_result() {
+        let (tx, rx) = unbounded();
+        let mut command = ExecuteCommand::new(tx);
+        command.shell_command = "sh -c".to_string();
+        command.command = vec!["true".to_string()];
+
+        let (changes_tx, changes_rx) = unbounded();
+        let interval = SharedInterval::new(crate::RunInterval::new(60.0).into());
+        let mut scheduler = Scheduler::new(command, interval).set_changes(changes_rx, false);
+
+        changes_tx.send(PathBuf::from("src/main.rs")).unwrap();
+        scheduler.wait(60.0);
+        scheduler.tick();
+        scheduler.tick();
+        drop(changes_tx);
+
+        let triggers: Vec<String> = rx
+            .try_iter()
+            .filter_map(|event| match event {
+                AppEvent::OutputUpdate(result) => Some(result.trigger),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(triggers, vec!["src/main.rs", ""]);
+    }
+
+    #[test]
+    fn cron_runs_are_not_told_an_interval() {
+        let (tx, _rx) = unbounded();
+        let mut run_interval = crate::RunInterval::new(60.0);
+        run_interval.set_kind(ScheduleKind::Cron(
+            crate::interval::CronSchedule::parse("*/5 * * * *").unwrap(),
+        ));
+        let scheduler = Scheduler::new(
+            ExecuteCommand::new(tx),
+            SharedInterval::new(run_interval.into()),
+        );
+
+        let env = scheduler.run_env(0);
+        assert!(env.iter().any(|(name, _)| name == "HWATCH_RUN"));
+        assert!(!env.iter().any(|(name, _)| name == "HWATCH_INTERVAL"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_see_the_previous_status_and_output() {
+        let (tx, rx) = unbounded();
+        let mut command = ExecuteCommand::new(tx);
+        command.shell_command = "sh -c".to_string();
+        command.command = vec![
+            "echo run=$HWATCH_RUN prev=$HWATCH_PREV_STATUS every=$HWATCH_INTERVAL; cat \"$HWATCH_PREV_OUTPUT_FILE\"; exit 3"
+                .to_string(),
+        ];
+
+        let interval = SharedInterval::new(crate::RunInterval::new(60.0).into());
+        let mut scheduler = Scheduler::new(command, interval);
+        scheduler.tick();
+        scheduler.tick();
+
+        let outputs: Vec<String> = rx
+            .try_iter()
+            .filter_map(|event| match event {
+                AppEvent::OutputUpdate(result) => Some(result.get_output()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            outputs,
+            vec![
+                "run=1 prev= every=60\n",
+                "run=2 prev=3 every=60\nrun=1 prev= every=60\n",
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_now_wakes_a_paused_scheduler_once() {
+        let (tx, rx) = unbounded();
+        let mut command = ExecuteCommand::new(tx);
+        command.shell_command = "sh -c".to_string();
+        command.command = vec!["true".to_string()];
+
+        let mut run_interval = crate::RunInterval::new(60.0);
+        run_interval.toggle_pause();
+        let interval = SharedInterval::new(run_interval.into());
+
+        let (run_now_tx, run_now_rx) = unbounded();
+        let mut scheduler = Scheduler::new(command, interval).set_run_now(run_now_rx);
+
+        scheduler.tick();
+        assert_eq!(scheduler.seq, 0);
+
+        run_now_tx.send(()).unwrap();
+        let start = Instant::now();
+        scheduler.wait(60.0);
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        scheduler.tick();
+        scheduler.tick();
+        assert_eq!(scheduler.seq, 1);
+        assert_eq!(received_commands(&rx).len(), 1);
+    }
+
+    #[test]
+    fn wait_falls_back_to_the_interval_when_channels_close() {
+        let (tx, _rx) = unbounded();
+        let command = ExecuteCommand::new(tx);
+        let interval = SharedInterval::new(crate::RunInterval::new(60.0).into());
+
+        let (changes_tx, changes_rx) = unbounded();
+        let (run_now_tx, run_now_rx) = unbounded::<()>();
+        let mut scheduler = Scheduler::new(command, interval)
+            .set_changes(changes_rx, true)
+            .set_run_now(run_now_rx);
+        drop(changes_tx);
+        drop(run_now_tx);
+
+        let start = Instant::now();
+        scheduler.wait(0.05);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert!(scheduler.changes.is_none());
+        assert!(scheduler.run_now.is_none());
+        assert!(scheduler.trigger.is_none());
+    }
+
+    #[test]
+    fn tick_publishes_the_next_run() {
+        let (tx, rx) = unbounded();
+        let command = ExecuteCommand::new(tx);
+        let mut run_interval = crate::RunInterval::new(60.0);
+        run_interval.toggle_pause();
+        let interval = SharedInterval::new(run_interval.into());
+        let scheduler = Scheduler::new(command, interval.clone());
+
+        assert_eq!(scheduler.schedule_next(None), 60.0);
+        assert!(interval.read().unwrap().next_run.is_none());
+
+        interval.write().unwrap().toggle_pause();
+        let before = Local::now();
+        assert_eq!(scheduler.schedule_next(None), 60.0);
+        let next_run = interval.read().unwrap().next_run.unwrap();
+        assert!(next_run >= before + TimeDelta::seconds(60));
+        assert!(matches!(rx.try_recv(), Ok(AppEvent::Redraw)));
+
+        let mut run_interval = interval.write().unwrap();
+        run_interval.set_kind(ScheduleKind::Align);
+        drop(run_interval);
+        assert!(scheduler.schedule_next(None) <= 60.0);
+    }
+
+    #[test]
+    fn result_order_feeds_the_backoff_policy() {
+        let (tx, _rx) = unbounded();
+        let interval = SharedInterval::new(crate::RunInterval::new(1.0).into());
+        interval
+            .write()
+            .unwrap()
+            .set_backoff(crate::interval::Backoff::new(2.0, 60.0));
+        let mut order = ResultOrder::new(tx, interval.clone());
+
+        let failed = CommandResult {
+            status: false,
+            ..Default::default()
+        };
+        order.push(1, Some(failed.clone()));
+        assert_eq!(interval.read().unwrap().effective_interval(), 1.0);
+        order.push(0, Some(failed));
+        assert_eq!(interval.read().unwrap().effective_interval(), 4.0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn count_stops_after_n_runs_and_exits() {
+        let (tx, rx) = unbounded();
+        let mut command = ExecuteCommand::new(tx);
+        command.shell_command = "sh -c".to_string();
+        command.command = vec!["true".to_string()];
+
+        let interval = SharedInterval::new(crate::RunInterval::new(0.01).into());
+        let handle = Scheduler::new(command, interval).set_count(Some(3)).spawn();
+        handle.join().unwrap();
+
+        let events: Vec<AppEvent> = rx
+            .try_iter()
+            .filter(|event| !matches!(event, AppEvent::Redraw))
+            .collect();
+        assert_eq!(events.len(), 4);
+        assert!(matches!(events[2], AppEvent::OutputUpdate(_)));
+        assert!(matches!(events[3], AppEvent::Exit));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn deadline_stops_a_long_wait() {
+        let (tx, rx) = unbounded();
+        let mut command = ExecuteCommand::new(tx);
+        command.shell_command = "sh -c".to_string();
+        command.command = vec!["true".to_string()];
+
+        let interval = SharedInterval::new(crate::RunInterval::new(60.0).into());
+        let start = Instant::now();
+        let handle = Scheduler::new(command, interval)
+            .set_deadline(Some(start + Duration::from_millis(200)))
+            .spawn();
+        handle.join().unwrap();
+
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(received_commands(&rx).len(), 1);
+    }
+
+    #[test]
+    fn overlap_policy_from_name() {
+        assert_eq!(OverlapPolicy::from_name("skip"), Some(OverlapPolicy::Skip));
+        assert_eq!(
+            OverlapPolicy::from_name("queue"),
+            Some(OverlapPolicy::Queue)
+        );
+        assert_eq!(OverlapPolicy::from_name("kill"), Some(OverlapPolicy::Kill));
+        assert_eq!(
+            OverlapPolicy::from_name("parallel"),
+            Some(OverlapPolicy::Parallel)
+        );
+        assert_eq!(OverlapPolicy::from_name("other"), None);
+    }
+}
@@ -0,0 +1,218 @@
+// Copyright (c) 2026 Blacknon. All rights reserved.
+// Use of this source code is governed by an MIT license
+// that can be found in the LICENSE file.
+
+// module
+use crossbeam_channel::{unbounded, RecvTimeoutError, Sender};
+use std::io::{BufRead, BufReader, Read};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+// local module
+use crate::common;
+use crate::event::AppEvent;
+use crate::exec::CommandResult;
+
+/// Idle time that ends a snapshot when no delimiter is given.
+pub const DEFAULT_SNAPSHOT_IDLE: Duration = Duration::from_millis(500);
+
+/// `command` of the results read from stdin.
+const STDIN_COMMAND: &str = "stdin";
+
+/// Splits a stream into snapshots, each sent as a result through
+/// `AppEvent::OutputUpdate` like the output of a run.
+pub struct StdinSnapshots {
+    delimiter: Option<String>,
+    idle: Option<Duration>,
+    is_compress: bool,
+    exit_on_eof: bool,
+    tx: Sender<AppEvent>,
+}
+
+/// Lines read since the last snapshot.
+#[derive(Default)]
+struct Pending {
+    data: Vec<u8>,
+    start_time: Option<String>,
+}
+
+impl StdinSnapshots {
+    pub fn new(tx: Sender<AppEvent>) -> Self {
+        Self {
+            delimiter: None,
+            idle: Some(DEFAULT_SNAPSHOT_IDLE),
+            is_compress: false,
+            exit_on_eof: false,
+            tx,
+        }
+    }
+
+    /// End a snapshot at each line equal to `delimiter`. The line itself is dropped.
+    pub fn set_delimiter(mut self, delimiter: Option<String>) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// End a snapshot once no line has arrived for `idle`.
+    pub fn set_idle(mut self, idle: Option<Duration>) -> Self {
+        self.idle = idle;
+        self
+    }
+
+    pub fn set_compress(mut self, is_compress: bool) -> Self {
+        self.is_compress = is_compress;
+        self
+    }
+
+    /// Send `AppEvent::Exit` once the stream is closed.
+    pub fn set_exit_on_eof(mut self, exit_on_eof: bool) -> Self {
+        self.exit_on_eof = exit_on_eof;
+        self
+    }
+
+    pub fn spawn<R: Read + Send + 'static>(self, reader: R) -> JoinHandle<()> {
+        let (lines_tx, lines) = unbounded();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(reader);
+            loop {
+                let mut line = vec![];
+                match reader.read_until(b'\n', &mut line) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {
+                        if lines_tx.send(line).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        thread::spawn(move || {
+            let mut pending = Pending::default();
+            loop {
+                // The idle timer only runs while there is something to send.
+                let line = match (self.idle, pending.start_time.is_some()) {
+                    (Some(idle), true) => match lines.recv_timeout(idle) {
+                        Ok(line) => Some(line),
+                        Err(RecvTimeoutError::Timeout) => {
+                            self.send(std::mem::take(&mut pending));
+                            continue;
+                        }
+                        Err(RecvTimeoutError::Disconnected) => None,
+                    },
+                    _ => lines.recv().ok(),
+                };
+
+                let Some(line) = line else {
+                    if pending.start_time.is_some() {
+                        self.send(pending);
+                    }
+                    break;
+                };
+
+                // Back-to-back delimiters do not make empty snapshots.
+                if self.is_delimiter(&line) {
+                    if pending.start_time.is_some() {
+                        self.send(std::mem::take(&mut pending));
+                    }
+                    continue;
+                }
+
+                pending.start_time.get_or_insert_with(common::now_str);
+                pending.data.extend_from_slice(&line);
+            }
+
+            if self.exit_on_eof {
+                let _ = self.tx.send(AppEvent::Exit);
+            }
+        })
+    }
+
+    fn is_delimiter(&self, line: &[u8]) -> bool {
+        let Some(delimiter) = &self.delimiter else {
+            return false;
+        };
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        line == delimiter.as_bytes()
+    }
+
+    fn send(&self, pending: Pending) {
+        let end_time = common::now_str();
+        let result = CommandResult {
+            timestamp: end_time.clone(),
+            command: STDIN_COMMAND.to_string(),
+            start_time: pending.start_time.unwrap_or_else(|| end_time.clone()),
+            end_time,
+            is_compress: self.is_compress,
+            ..Default::default()
+        }
+        .set_output(pending.data.clone())
+        .set_stdout(pending.data);
+
+        let _ = self.tx.send(AppEvent::OutputUpdate(Box::new(result)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel::Receiver;
+    use std::io::Write;
+
+    fn snapshots(rx: &Receiver<AppEvent>) -> Vec<String> {
+        rx.iter()
+            .map_while(|event| match event {
+                AppEvent::OutputUpdate(result) => Some(result.get_output()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn delimiter_lines_split_the_stream() {
+        let (tx, rx) = unbounded();
+        let input = "a\nb\n---\n---\nc\n---\nd".as_bytes();
+        StdinSnapshots::new(tx)
+            .set_delimiter(Some("---".to_string()))
+            .set_idle(None)
+            .set_exit_on_eof(true)
+            .spawn(input)
+            .join()
+            .unwrap();
+
+        assert_eq!(snapshots(&rx), vec!["a\nb\n", "c\n", "d"]);
+    }
+
+    #[test]
+    fn delimiters_without_lines_between_them_send_nothing() {
+        let (tx, rx) = unbounded();
+        StdinSnapshots::new(tx)
+            .set_delimiter(Some("---".to_string()))
+            .set_idle(None)
+            .set_exit_on_eof(true)
+            .spawn("---\n---\n".as_bytes())
+            .join()
+            .unwrap();
+
+        assert!(snapshots(&rx).is_empty());
+    }
+
+    #[test]
+    fn idle_time_splits_the_stream() {
+        let (tx, rx) = unbounded();
+        let (reader, mut writer) = std::io::pipe().unwrap();
+        let handle = StdinSnapshots::new(tx)
+            .set_idle(Some(Duration::from_millis(100)))
+            .set_exit_on_eof(true)
+            .spawn(reader);
+
+        writer.write_all(b"first\n").unwrap();
+        thread::sleep(Duration::from_millis(400));
+        writer.write_all(b"second\nthird\n").unwrap();
+        drop(writer);
+        handle.join().unwrap();
+
+        assert_eq!(snapshots(&rx), vec!["first\n", "second\nthird\n"]);
+    }
+}
@@ -0,0 +1,128 @@
+// Copyright (c) 2026 Blacknon. All rights reserved.
+// Use of this source code is governed by an MIT license
+// that can be found in the LICENSE file.
+
+use crate::exec::CommandResult;
+
+/// What a run means once `--stable-for` is applied.
+pub enum Stability {
+    /// Same output as the recorded result.
+    Unchanged,
+
+    /// New output that has not been seen often enough yet.
+    Pending,
+
+    /// The held back output went away before it became stable.
+    Flap,
+
+    /// New output seen in enough consecutive runs. Record this result.
+    Changed(Box<CommandResult>),
+}
+
+/// Holds back a changed result until the same output has been seen in
+/// `runs` consecutive runs.
+pub struct StableFilter {
+    runs: u32,
+    pending: Option<CommandResult>,
+    seen: u32,
+}
+
+impl StableFilter {
+    pub fn new(runs: u32) -> Self {
+        Self {
+            runs,
+            pending: None,
+            seen: 0,
+        }
+    }
+
+    /// `unchanged` tells whether `result` matches the recorded result, and
+    /// `equivalent` compares two results the same way.
+    pub fn check(
+        &mut self,
+        result: CommandResult,
+        unchanged: bool,
+        equivalent: impl Fn(&CommandResult, &CommandResult) -> bool,
+    ) -> Stability {
+        if unchanged {
+            self.seen = 0;
+            return match self.pending.take() {
+                Some(_) => Stability::Flap,
+                None => Stability::Unchanged,
+            };
+        }
+
+        let same_as_pending = self
+            .pending
+            .as_ref()
+            .is_some_and(|pending| equivalent(pending, &result));
+
+        let flapped = self.pending.is_some() && !same_as_pending;
+        self.seen = match same_as_pending {
+            true => self.seen + 1,
+            false => 1,
+        };
+
+        if self.seen >= self.runs {
+            self.pending = None;
+            self.seen = 0;
+            return Stability::Changed(Box::new(result));
+        }
+
+        self.pending = Some(result);
+        match flapped {
+            true => Stability::Flap,
+            false => Stability::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(output: &str) -> CommandResult {
+        CommandResult::default().set_output(output.as_bytes().to_vec())
+    }
+
+    fn check(filter: &mut StableFilter, recorded: &str, output: &str) -> Stability {
+        filter.check(result(output), recorded == output, |a, b| {
+            a.get_output() == b.get_output()
+        })
+    }
+
+    #[test]
+    fn change_is_recorded_after_n_runs() {
+        let mut filter = StableFilter::new(3);
+        assert!(matches!(check(&mut filter, "a", "a"), Stability::Unchanged));
+        assert!(matches!(check(&mut filter, "a", "b"), Stability::Pending));
+        assert!(matches!(check(&mut filter, "a", "b"), Stability::Pending));
+        match check(&mut filter, "a", "b") {
+            Stability::Changed(result) => assert_eq!(result.get_output(), "b"),
+            _ => panic!("the change was not recorded"),
+        }
+        assert!(matches!(check(&mut filter, "b", "b"), Stability::Unchanged));
+    }
+
+    #[test]
+    fn flapping_output_is_never_recorded() {
+        let mut filter = StableFilter::new(2);
+        assert!(matches!(check(&mut filter, "a", "b"), Stability::Pending));
+        assert!(matches!(check(&mut filter, "a", "a"), Stability::Flap));
+        assert!(matches!(check(&mut filter, "a", "b"), Stability::Pending));
+        assert!(matches!(check(&mut filter, "a", "c"), Stability::Flap));
+        assert!(matches!(
+            check(&mut filter, "a", "c"),
+            Stability::Changed(_)
+        ));
+    }
+
+    #[test]
+    fn one_run_records_every_change() {
+        let mut filter = StableFilter::new(1);
+        assert!(matches!(
+            check(&mut filter, "a", "b"),
+            Stability::Changed(_)
+        ));
+    }
+}
@@ -15,15 +15,19 @@ use tui::style::Color;
 
 // local module
 use crate::app::App;
-use crate::common::OutputMode;
+use crate::baseline::Baseline;
+use crate::common::{ExitOnMatch, ExitOnStatus, OutputMode};
 use crate::event::AppEvent;
 use crate::exec::CommandResult;
+use crate::header::LogSource;
+use crate::history::HistoryColumn;
 use crate::keymap::{default_keymap, Keymap};
 
 use hwatch_diffmode::DiffMode;
 
 // local const
 use crate::SharedInterval;
+use crate::SharedPtySize;
 use crate::DEFAULT_TAB_SIZE;
 
 /// Struct at run hwatch on tui
@@ -38,6 +42,11 @@ pub struct View {
     keymap: Keymap,
     beep: bool,
     exit_on_change: Option<u32>,
+    exit_on_status: Option<ExitOnStatus>,
+    exit_on_match: Option<ExitOnMatch>,
+    stable_for: Option<u32>,
+    baseline: Option<Baseline>,
+    pause_on_exit: bool,
     border: bool,
     scroll_bar: bool,
     mouse_events: bool,
@@ -57,7 +66,12 @@ pub struct View {
     ignore_spaceblock: bool,
     summary_enabled: bool,
     enable_summary_char: bool,
+    history_columns: Vec<HistoryColumn>,
+    pty_size: Option<SharedPtySize>,
+    run_now: Option<Sender<()>>,
     log_path: String,
+    log_heartbeat: bool,
+    log_source: Option<LogSource>,
 }
 
 ///
@@ -73,6 +87,11 @@ impl View {
             keymap: default_keymap(),
             beep: false,
             exit_on_change: None,
+            exit_on_status: None,
+            exit_on_match: None,
+            stable_for: None,
+            baseline: None,
+            pause_on_exit: false,
             border: false,
             scroll_bar: false,
             mouse_events: false,
@@ -92,7 +111,12 @@ impl View {
             ignore_spaceblock: false,
             summary_enabled: true,
             enable_summary_char: false,
+            history_columns: vec![],
+            pty_size: None,
+            run_now: None,
             log_path: "".to_string(),
+            log_heartbeat: false,
+            log_source: None,
         }
     }
 
@@ -136,6 +160,31 @@ impl View {
         self
     }
 
+    pub fn set_exit_on_status(mut self, exit_on_status: Option<ExitOnStatus>) -> Self {
+        self.exit_on_status = exit_on_status;
+        self
+    }
+
+    pub fn set_exit_on_match(mut self, exit_on_match: Option<ExitOnMatch>) -> Self {
+        self.exit_on_match = exit_on_match;
+        self
+    }
+
+    pub fn set_stable_for(mut self, stable_for: Option<u32>) -> Self {
+        self.stable_for = stable_for;
+        self
+    }
+
+    pub fn set_baseline(mut self, baseline: Option<Baseline>) -> Self {
+        self.baseline = baseline;
+        self
+    }
+
+    pub fn set_pause_on_exit(mut self, pause_on_exit: bool) -> Self {
+        self.pause_on_exit = pause_on_exit;
+        self
+    }
+
     pub fn set_border(mut self, border: bool) -> Self {
         self.border = border;
         self
@@ -222,17 +271,43 @@ impl View {
         self
     }
 
+    pub fn set_history_columns(mut self, history_columns: Vec<HistoryColumn>) -> Self {
+        self.history_columns = history_columns;
+        self
+    }
+
+    pub fn set_pty_size(mut self, pty_size: SharedPtySize) -> Self {
+        self.pty_size = Some(pty_size);
+        self
+    }
+
+    pub fn set_run_now(mut self, run_now: Sender<()>) -> Self {
+        self.run_now = Some(run_now);
+        self
+    }
+
     pub fn set_logfile(mut self, log_path: String) -> Self {
         self.log_path = log_path;
         self
     }
 
+    pub fn set_log_heartbeat(mut self, log_heartbeat: bool) -> Self {
+        self.log_heartbeat = log_heartbeat;
+        self
+    }
+
+    /// Show the results of a logfile without running a command.
+    pub fn set_log_source(mut self, log_source: Option<LogSource>) -> Self {
+        self.log_source = log_source;
+        self
+    }
+
     pub fn start(
         &mut self,
         tx: Sender<AppEvent>,
         rx: Receiver<AppEvent>,
         exist_results: Vec<CommandResult>,
-    ) -> Result<(), Box<dyn Error>> {
+    ) -> Result<i32, Box<dyn Error>> {
         let mut terminal = setup_terminal()?;
 
         {
@@ -263,6 +338,6 @@ impl View {
             println!("{err:?}")
         }
 
-        Ok(())
+        Ok(app.get_exit_code())
     }
 }
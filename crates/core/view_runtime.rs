@@ -41,9 +41,15 @@ impl View {
         app.set_limit(self.limit);
         app.set_beep(self.beep);
         app.set_exit_on_change(self.exit_on_change);
+        app.set_exit_on_status(self.exit_on_status);
+        app.set_exit_on_match(self.exit_on_match.clone());
+        app.set_pause_on_exit(self.pause_on_exit);
+        app.set_stable_for(self.stable_for);
+        app.set_baseline(self.baseline.clone());
         app.set_border(self.border);
         app.set_scroll_bar(self.scroll_bar);
         app.set_logpath(self.log_path.clone());
+        app.set_log_heartbeat(self.log_heartbeat);
         app.set_ansi_color(self.color);
         app.show_history(self.show_ui);
         app.show_ui(self.show_ui);
@@ -58,6 +64,16 @@ impl View {
         app.set_ignore_spaceblock(self.ignore_spaceblock);
         app.set_summary_enabled(self.summary_enabled);
         app.set_enable_summary_char(self.enable_summary_char);
+        app.set_history_columns(self.history_columns.clone());
+        if let Some(pty_size) = &self.pty_size {
+            app.set_pty_size(pty_size.clone());
+        }
+        if let Some(run_now) = &self.run_now {
+            app.set_run_now(run_now.clone());
+        }
+        if let Some(log_source) = &self.log_source {
+            app.set_log_source(log_source.clone());
+        }
 
         Ok(())
     }
@@ -130,6 +130,18 @@ impl<'a> WatchArea<'a> {
         self.area.height as i16
     }
 
+    /// Columns and rows available for the output, excluding the border.
+    pub fn get_content_size(&self) -> (u16, u16) {
+        match (self.border, self.hide_header) {
+            (true, true) => (self.area.width.saturating_sub(1), self.area.height),
+            (true, false) => (
+                self.area.width.saturating_sub(1),
+                self.area.height.saturating_sub(1),
+            ),
+            (false, _) => (self.area.width, self.area.height),
+        }
+    }
+
     ///
     pub fn update_output(&mut self, data: Vec<Line<'a>>) {
         // update data
@@ -354,3 +354,423 @@ fn batch_mode_with_file_driven_state_change_is_detected() {
     let status = mutator.wait_with_output().unwrap().status;
     assert!(status.success());
 }
+
+#[cfg(unix)]
+#[test]
+fn batch_mode_with_count_exits_with_the_last_exit_code() {
+    let temp = tempdir().unwrap();
+    let counter_path = temp.path().join("counter.txt");
+    let script_path = temp.path().join("count.sh");
+
+    write_executable_script(
+        &script_path,
+        format!(
+            "#!/bin/sh\ncount_file=\"{}\"\ncount=0\nif [ -f \"$count_file\" ]; then\n  count=$(cat \"$count_file\")\nfi\ncount=$((count + 1))\nprintf '%s' \"$count\" > \"$count_file\"\nexit $count\n",
+            counter_path.display()
+        )
+        .as_str(),
+    );
+
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args([
+        "-b",
+        "--count",
+        "3",
+        "-n",
+        "0.05",
+        "sh",
+        script_path.to_str().unwrap(),
+    ]);
+    cmd.timeout(Duration::from_secs(5));
+
+    cmd.assert().code(3);
+
+    let counter = fs::read_to_string(&counter_path).unwrap();
+    assert_eq!(counter, "3");
+}
+
+#[test]
+fn batch_mode_with_duration_exits_after_the_deadline() {
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args(["-b", "--duration", "0.3", "-n", "60", "echo", "hello"]);
+    cmd.timeout(Duration::from_secs(5));
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("hello"));
+}
+
+#[cfg(unix)]
+#[test]
+fn batch_mode_with_until_failure_exits_with_the_failed_exit_code() {
+    let temp = tempdir().unwrap();
+    let marker_path = temp.path().join("marker");
+
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args([
+        "-b",
+        "--until-failure",
+        "-n",
+        "0.05",
+        format!("test ! -f {0} || exit 7; touch {0}", marker_path.display()).as_str(),
+    ]);
+    cmd.timeout(Duration::from_secs(5));
+
+    cmd.assert().code(7);
+}
+
+#[cfg(unix)]
+#[test]
+fn batch_mode_with_until_match_exits_once_the_output_matches() {
+    let temp = tempdir().unwrap();
+    let marker_path = temp.path().join("marker");
+
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args([
+        "-b",
+        "--until-match",
+        "^pod Running$",
+        "--ignore-spaceblock",
+        "-n",
+        "0.05",
+        format!(
+            "if [ -f {0} ]; then echo 'pod    Running'; else touch {0}; echo 'pod Pending'; fi",
+            marker_path.display()
+        )
+        .as_str(),
+    ]);
+    cmd.timeout(Duration::from_secs(5));
+
+    let assert = cmd.assert().success();
+    let stdout = stdout_text_without_ansi(&assert);
+    assert!(stdout.contains("pod Pending"));
+    assert!(stdout.contains("pod    Running"));
+}
+
+#[cfg(unix)]
+#[test]
+fn batch_mode_with_stable_for_skips_flapping_output() {
+    let temp = tempdir().unwrap();
+    let counter_path = temp.path().join("counter.txt");
+
+    // a a b a b b b c c c ...: only `c` lasts for three runs.
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args([
+        "-b",
+        "--stable-for",
+        "3",
+        "-g",
+        "1",
+        "-n",
+        "0.05",
+        format!(
+            "n=$(cat {0} 2>/dev/null || echo 0); n=$((n + 1)); echo $n > {0}; case $n in 1|2|4|6) echo a;; 3|5) echo b;; *) echo c;; esac",
+            counter_path.display()
+        )
+        .as_str(),
+    ]);
+    cmd.timeout(Duration::from_secs(5));
+
+    let assert = cmd.assert().success();
+    let stdout = stdout_text_without_ansi(&assert);
+    assert!(stdout.contains("a\n"));
+    assert!(!stdout.contains("b\n"));
+    assert!(stdout.contains("c\n"));
+
+    let counter = fs::read_to_string(&counter_path).unwrap();
+    assert_eq!(counter.trim(), "9");
+}
+
+#[cfg(unix)]
+#[test]
+fn batch_mode_runs_the_command_with_cwd_and_env() {
+    let temp = tempdir().unwrap();
+    let env_file = temp.path().join("watch.env");
+    fs::write(
+        &env_file,
+        "# settings\nexport GREETING=hello\nTARGET=file\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args([
+        "-b",
+        "--count",
+        "1",
+        "--cwd",
+        temp.path().to_str().unwrap(),
+        "--env-file",
+        env_file.to_str().unwrap(),
+        "--env",
+        "TARGET=world",
+        "echo \"$GREETING $TARGET\"; ls",
+    ]);
+    cmd.timeout(Duration::from_secs(5));
+
+    let assert = cmd.assert().success();
+    let stdout = stdout_text_without_ansi(&assert);
+    assert!(stdout.contains("hello world\n"));
+    assert!(stdout.contains("watch.env\n"));
+}
+
+#[test]
+fn align_rejects_an_interval_that_is_not_positive() {
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args(["-b", "-n", "0", "--align", "true"]);
+    cmd.timeout(Duration::from_secs(5));
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--align"));
+}
+
+#[test]
+fn malformed_env_file_is_rejected() {
+    let temp = tempdir().unwrap();
+    let env_file = temp.path().join("broken.env");
+    fs::write(&env_file, "NOT AN ASSIGNMENT\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args(["-b", "--env-file", env_file.to_str().unwrap(), "true"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("line 1"));
+}
+
+#[test]
+fn batch_mode_watches_a_file_instead_of_a_command() {
+    let temp = tempdir().unwrap();
+    let status_path = temp.path().join("status.txt");
+    fs::write(&status_path, "ready\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args([
+        "-b",
+        "--count",
+        "2",
+        "-n",
+        "0.05",
+        "--file",
+        status_path.to_str().unwrap(),
+    ]);
+    cmd.timeout(Duration::from_secs(5));
+
+    let assert = cmd.assert().success();
+    let stdout = stdout_text_without_ansi(&assert);
+    assert_eq!(stdout.matches("ready\n").count(), 1);
+}
+
+#[test]
+fn batch_mode_chgexit_exits_on_the_first_run_that_differs_from_the_baseline() {
+    let temp = tempdir().unwrap();
+    let expected_path = temp.path().join("expected.txt");
+    let status_path = temp.path().join("status.txt");
+    fs::write(&expected_path, "ready\n").unwrap();
+    fs::write(&status_path, "degraded\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args([
+        "-b",
+        "-g",
+        "-n",
+        "0.05",
+        "--baseline",
+        expected_path.to_str().unwrap(),
+        "--file",
+        status_path.to_str().unwrap(),
+    ]);
+    cmd.timeout(Duration::from_secs(5));
+
+    let assert = cmd.assert().success();
+    let stdout = stdout_text_without_ansi(&assert);
+    assert!(stdout.contains("degraded"));
+}
+
+#[test]
+fn baseline_rejects_a_missing_file() {
+    let temp = tempdir().unwrap();
+    let expected_path = temp.path().join("missing.txt");
+
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args(["-b", "--baseline", expected_path.to_str().unwrap(), "true"]);
+    cmd.timeout(Duration::from_secs(5));
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--baseline"));
+}
+
+#[test]
+fn batch_mode_expect_prints_the_diff_and_fails_on_a_mismatch() {
+    let temp = tempdir().unwrap();
+    let expected_path = temp.path().join("expected.txt");
+    fs::write(&expected_path, "ready\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args([
+        "-b",
+        "--count",
+        "2",
+        "-n",
+        "0.05",
+        "-d",
+        "line",
+        "--expect",
+        expected_path.to_str().unwrap(),
+        "echo degraded",
+    ]);
+    cmd.timeout(Duration::from_secs(5));
+
+    let assert = cmd.assert().code(1);
+    let stdout = stdout_text_without_ansi(&assert);
+    assert_eq!(stdout.matches("-  ready").count(), 2);
+    assert_eq!(stdout.matches("+  degraded").count(), 2);
+}
+
+#[test]
+fn batch_mode_expect_passes_quietly_when_every_run_matches() {
+    let temp = tempdir().unwrap();
+    let expected_path = temp.path().join("expected.txt");
+    fs::write(&expected_path, "ready\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args([
+        "-b",
+        "--count",
+        "2",
+        "-n",
+        "0.05",
+        "--expect",
+        expected_path.to_str().unwrap(),
+        "echo ready",
+    ]);
+    cmd.timeout(Duration::from_secs(5));
+
+    cmd.assert().success().stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn batch_mode_update_expected_writes_the_output_to_the_file() {
+    let temp = tempdir().unwrap();
+    let expected_path = temp.path().join("expected.txt");
+
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args([
+        "-b",
+        "--count",
+        "2",
+        "-n",
+        "0.05",
+        "--expect",
+        expected_path.to_str().unwrap(),
+        "--update-expected",
+        "echo ready",
+    ]);
+    cmd.timeout(Duration::from_secs(5));
+
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("updated").count(1));
+    assert_eq!(fs::read_to_string(&expected_path).unwrap(), "ready\n");
+}
+
+#[test]
+fn batch_mode_marks_output_cut_at_max_output_bytes() {
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args([
+        "-b",
+        "--count",
+        "1",
+        "--max-output-bytes",
+        "6",
+        "--",
+        "printf 'first\\nsecond\\n'",
+    ]);
+    cmd.timeout(Duration::from_secs(5));
+
+    let assert = cmd.assert().success();
+    let stdout = stdout_text_without_ansi(&assert);
+    assert!(stdout.contains("first\n"));
+    assert!(stdout.contains("hwatch: output truncated (--max-output-bytes)"));
+    assert!(!stdout.contains("second"));
+}
+
+#[test]
+fn batch_mode_records_only_the_last_retry() {
+    let temp = tempdir().unwrap();
+    let counter = temp.path().join("attempts");
+
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args([
+        "-b",
+        "--count",
+        "1",
+        "--retries",
+        "3",
+        "--retry-delay",
+        "0.05",
+        "--",
+        &format!(
+            "echo x >> {0}; n=$(wc -l < {0}); echo attempt $n; [ $n -ge 3 ]",
+            counter.display()
+        ),
+    ]);
+    cmd.timeout(Duration::from_secs(5));
+
+    let assert = cmd.assert().success();
+    let stdout = stdout_text_without_ansi(&assert);
+    assert!(stdout.contains("attempt 3"));
+    assert!(!stdout.contains("attempt 1"));
+    assert!(!stdout.contains("attempt 2"));
+    assert_eq!(fs::read_to_string(&counter).unwrap().lines().count(), 3);
+}
+
+#[test]
+fn replay_rejects_a_missing_logfile_and_a_command() {
+    let temp = tempdir().unwrap();
+    let logfile = temp.path().join("missing.jsonl");
+
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args(["--replay", logfile.to_str().unwrap()]);
+    cmd.timeout(Duration::from_secs(5));
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--replay"));
+    assert!(!logfile.exists());
+
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args(["--replay", logfile.to_str().unwrap(), "--", "date"]);
+    cmd.timeout(Duration::from_secs(5));
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn follow_log_rejects_a_missing_logfile() {
+    let temp = tempdir().unwrap();
+    let logfile = temp.path().join("missing.jsonl");
+
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args(["--follow-log", logfile.to_str().unwrap()]);
+    cmd.timeout(Duration::from_secs(5));
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--follow-log"));
+    assert!(!logfile.exists());
+}
+
+#[test]
+fn batch_mode_splits_stdin_into_snapshots() {
+    let mut cmd = Command::cargo_bin("hwatch").unwrap();
+    cmd.args(["-b", "--stdin-snapshots", "--snapshot-delimiter", "---"]);
+    cmd.write_stdin("one\n---\none\n---\ntwo\n");
+    cmd.timeout(Duration::from_secs(5));
+
+    let assert = cmd.assert().success();
+    let stdout = stdout_text_without_ansi(&assert);
+    assert_eq!(stdout.matches("one\n").count(), 1);
+    assert!(stdout.contains("two\n"));
+    assert!(!stdout.contains("---"));
+}